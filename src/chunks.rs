@@ -0,0 +1,348 @@
+pub mod chunks {
+	use crate::dungeon::dungeon::Dungeon;
+	use crate::engine::engine::Coordinate;
+	use crate::entity::store::EntityId;
+	use crate::noise::value_noise::{hash, noise};
+	use crate::world::world::area::{Area, Terrain, Tile, WorldObject};
+	use json::{object, JsonValue};
+	use rand::rngs::StdRng;
+	use rand::{Rng, SeedableRng};
+	use std::collections::{HashMap, HashSet};
+
+	/// The width and height of a chunk in tiles
+	pub const CHUNK_SIZE: i32 = 16;
+
+	/// Each terrain's number in the compact form of a chunk
+	const TERRAINS: [Terrain; 8] = [
+		Terrain::Floor,
+		Terrain::Water,
+		Terrain::Lava,
+		Terrain::Rubble,
+		Terrain::Grass,
+		Terrain::Meadow,
+		Terrain::Forest,
+		Terrain::Mountain,
+	];
+	/// The definitions of what may be found lying around in a chunk
+	const ITEMS: [&str; 3] = ["gold", "potion", "sword"];
+	/// How far from the origin open ground is looked for to start a game on
+	const SPAWN_SEARCH_RADIUS: i32 = 4 * CHUNK_SIZE;
+	/// The bit set in a tile's code for the first of `WorldObject::DEFINED` on it, above the terrain's number
+	const FIRST_OBJECT_BIT: u8 = 8;
+	const _: () = assert!(TERRAINS.len() <= 8 && WorldObject::DEFINED.len() <= 5);
+
+	/// An unbounded world generated a chunk at a time as the player approaches.
+	/// Terrain comes from noise over world coordinates, so chunks line up wherever generation starts,
+	/// and what's in each chunk comes from a random generator seeded by the world seed and the chunk.
+	/// Chunks the player has left far behind are taken out of the area and kept in a compact form.
+	pub struct ChunkedWorld {
+		pub seed: u64,
+		/// How many chunks around the player's own are generated or restored
+		pub load_radius: i32,
+		/// How many chunks around the player's own are kept in the area before being evicted
+		pub keep_radius: i32,
+		loaded: HashSet<(i32, i32)>,
+		evicted: HashMap<(i32, i32), EvictedChunk>,
+	}
+
+	/// A chunk taken out of the area
+	struct EvictedChunk {
+		/// Run-length encoded tile codes
+		tiles: Vec<u8>,
+		/// The entities that were in the chunk and anything they held, as from `Entities::entity_to_json`
+		entities: Vec<JsonValue>,
+	}
+
+	impl ChunkedWorld {
+		pub fn new(seed: u64) -> ChunkedWorld {
+			ChunkedWorld {
+				seed,
+				load_radius: 2,
+				keep_radius: 3,
+				loaded: HashSet::new(),
+				evicted: HashMap::new(),
+			}
+		}
+
+		/// Load the chunks around the nearest open ground to the origin, to start a game there.
+		/// If there's none nearby, the origin and the tiles around it are cleared instead.
+		pub fn dungeon(&mut self) -> Dungeon {
+			let mut area = Area::new(None);
+			let spawn_point = self.spawn_point();
+			let center = spawn_point.unwrap_or(Coordinate::new(0, 0));
+			self.update(&mut area, center);
+			if spawn_point.is_none() {
+				for x in -1..=1 {
+					for y in -1..=1 {
+						let mut tile = area.get_tile_at(x, y);
+						tile.terrain = Terrain::Floor;
+						tile.contents
+							.retain(|object| matches!(object, WorldObject::ENTITY(_)));
+						area.set_tile(x, y, tile);
+					}
+				}
+			}
+			Dungeon {
+				area,
+				spawn_point: center,
+			}
+		}
+
+		/// The chunk a tile is in
+		pub fn chunk_of(position: Coordinate) -> (i32, i32) {
+			(
+				position.x.div_euclid(CHUNK_SIZE),
+				position.y.div_euclid(CHUNK_SIZE),
+			)
+		}
+
+		pub fn is_loaded(&self, chunk: (i32, i32)) -> bool {
+			self.loaded.contains(&chunk)
+		}
+
+		pub fn is_evicted(&self, chunk: (i32, i32)) -> bool {
+			self.evicted.contains_key(&chunk)
+		}
+
+		pub fn loaded_count(&self) -> usize {
+			self.loaded.len()
+		}
+
+		/// Make sure the chunks near `center` are in the area, and evict those too far from it
+		pub fn update(&mut self, area: &mut Area, center: Coordinate) {
+			let (cx, cy) = ChunkedWorld::chunk_of(center);
+			let mut far: Vec<(i32, i32)> = self
+				.loaded
+				.iter()
+				.filter(|(x, y)| {
+					(x - cx).abs() > self.keep_radius || (y - cy).abs() > self.keep_radius
+				})
+				.copied()
+				.collect();
+			far.sort_unstable();
+			for chunk in far {
+				self.evict(area, chunk);
+			}
+			for x in cx - self.load_radius..=cx + self.load_radius {
+				for y in cy - self.load_radius..=cy + self.load_radius {
+					if self.loaded.contains(&(x, y)) {
+						continue;
+					}
+					match self.evicted.remove(&(x, y)) {
+						Some(evicted) => restore(area, (x, y), &evicted),
+						None => self.generate(area, (x, y)),
+					}
+					self.loaded.insert((x, y));
+				}
+			}
+		}
+
+		/// Fill a chunk with terrain, and maybe something lying around
+		fn generate(&self, area: &mut Area, chunk: (i32, i32)) {
+			let origin = Coordinate::new(chunk.0 * CHUNK_SIZE, chunk.1 * CHUNK_SIZE);
+			for x in origin.x..origin.x + CHUNK_SIZE {
+				for y in origin.y..origin.y + CHUNK_SIZE {
+					area.set_tile(x, y, self.tile_at(x, y));
+				}
+			}
+			let mut rng = StdRng::seed_from_u64(hash(self.seed ^ 0x5eed, chunk.0, chunk.1));
+			if rng.gen_bool(0.3) {
+				let position = Coordinate::new(
+					origin.x + rng.gen_range(0..CHUNK_SIZE),
+					origin.y + rng.gen_range(0..CHUNK_SIZE),
+				);
+				let item = ITEMS[rng.gen_range(0..ITEMS.len())];
+				if area.is_walkable_at(position.x, position.y) {
+					area.spawn(item, position);
+				}
+			}
+		}
+
+		/// The generated tile at a position: open ground, with rock where the noise is high
+		/// and water where it's low
+		fn tile_at(&self, x: i32, y: i32) -> Tile {
+			let height = 0.7 * noise(self.seed, x, y, 12.0) + 0.3 * noise(self.seed + 1, x, y, 4.0);
+			let cover = noise(self.seed + 2, x, y, 8.0);
+			if height > 0.68 {
+				Tile::wall()
+			} else if height < 0.28 {
+				Tile::terrain(Terrain::Water)
+			} else if cover > 0.65 {
+				Tile::terrain(Terrain::Grass)
+			} else if cover < 0.2 {
+				Tile::terrain(Terrain::Rubble)
+			} else {
+				Tile::new(None)
+			}
+		}
+
+		/// The nearest tile to the origin that's open ground with open ground all around it,
+		/// if there's one within `SPAWN_SEARCH_RADIUS`
+		fn spawn_point(&self) -> Option<Coordinate> {
+			let open = |x: i32, y: i32| {
+				(-1..=1).all(|dx| {
+					(-1..=1).all(|dy| {
+						let tile = self.tile_at(x + dx, y + dy);
+						tile.is_walkable() && tile.terrain.damage() == 0
+					})
+				})
+			};
+			for radius in 0..=SPAWN_SEARCH_RADIUS {
+				for x in -radius..=radius {
+					for y in -radius..=radius {
+						if (x.abs() == radius || y.abs() == radius) && open(x, y) {
+							return Some(Coordinate::new(x, y));
+						}
+					}
+				}
+			}
+			None
+		}
+
+		/// Take a chunk's tiles and the entities in it out of the area, keeping them in compact form
+		fn evict(&mut self, area: &mut Area, chunk: (i32, i32)) {
+			let origin = Coordinate::new(chunk.0 * CHUNK_SIZE, chunk.1 * CHUNK_SIZE);
+			let mut ids: Vec<EntityId> = vec![];
+			for x in origin.x..origin.x + CHUNK_SIZE {
+				for y in origin.y..origin.y + CHUNK_SIZE {
+					ids.extend(area.entities_at(x, y));
+				}
+			}
+			let mut i = 0;
+			while i < ids.len() {
+				if let Some(inventory) = area.entities.inventories.get(&ids[i]) {
+					ids.extend(inventory);
+				}
+				i += 1;
+			}
+			let entities = ids
+				.iter()
+				.map(|id| area.entities.entity_to_json(*id))
+				.collect();
+			for id in ids {
+				area.remove_entity(id);
+			}
+			let mut compact: Vec<u8> = vec![];
+			for x in origin.x..origin.x + CHUNK_SIZE {
+				for y in origin.y..origin.y + CHUNK_SIZE {
+					let code = encode(&area.get_tile_at(x, y));
+					match compact.len() {
+						len if len >= 2
+							&& compact[len - 1] == code
+							&& compact[len - 2] < u8::MAX =>
+						{
+							compact[len - 2] += 1
+						}
+						_ => compact.extend([1, code]),
+					}
+					if let Some(x_col) = area.map.get_mut(&x) {
+						x_col.remove(&y);
+					}
+				}
+				if area.map.get(&x).is_some_and(|x_col| x_col.is_empty()) {
+					area.map.remove(&x);
+				}
+			}
+			self.loaded.remove(&chunk);
+			self.evicted.insert(
+				chunk,
+				EvictedChunk {
+					tiles: compact,
+					entities,
+				},
+			);
+		}
+
+		pub fn to_json(&self) -> JsonValue {
+			let mut loaded: Vec<(i32, i32)> = self.loaded.iter().copied().collect();
+			loaded.sort_unstable();
+			let mut evicted: Vec<(i32, i32)> = self.evicted.keys().copied().collect();
+			evicted.sort_unstable();
+			let loaded: Vec<JsonValue> = loaded.iter().map(|(x, y)| json::array![*x, *y]).collect();
+			let evicted: Vec<JsonValue> = evicted
+				.iter()
+				.map(|(x, y)| {
+					let chunk = &self.evicted[&(*x, *y)];
+					json::array![*x, *y, chunk.tiles.clone(), chunk.entities.clone()]
+				})
+				.collect();
+			object!(
+				"seed" => self.seed.to_string(),
+				"load_radius" => self.load_radius,
+				"keep_radius" => self.keep_radius,
+				"loaded" => loaded,
+				"evicted" => evicted,
+			)
+		}
+
+		pub fn from_json(value: &JsonValue) -> Option<ChunkedWorld> {
+			let mut world = ChunkedWorld::new(value["seed"].as_str()?.parse().ok()?);
+			world.load_radius = value["load_radius"].as_i32()?;
+			world.keep_radius = value["keep_radius"].as_i32()?;
+			for chunk in value["loaded"].members() {
+				world
+					.loaded
+					.insert((chunk[0].as_i32()?, chunk[1].as_i32()?));
+			}
+			for chunk in value["evicted"].members() {
+				let mut tiles = vec![];
+				for byte in chunk[2].members() {
+					tiles.push(byte.as_u8()?);
+				}
+				let entities = chunk[3].members().cloned().collect();
+				world.evicted.insert(
+					(chunk[0].as_i32()?, chunk[1].as_i32()?),
+					EvictedChunk { tiles, entities },
+				);
+			}
+			Some(world)
+		}
+	}
+
+	/// A tile as its terrain's number, with a bit set for each object on it besides entities
+	fn encode(tile: &Tile) -> u8 {
+		let mut code = TERRAINS
+			.iter()
+			.position(|terrain| *terrain == tile.terrain)
+			.unwrap_or(0) as u8;
+		for (i, object) in WorldObject::DEFINED.iter().enumerate() {
+			if tile.contents.iter().any(|other| other.id() == object.id()) {
+				code |= FIRST_OBJECT_BIT << i;
+			}
+		}
+		code
+	}
+
+	fn decode(code: u8) -> Tile {
+		let mut tile = Tile::terrain(TERRAINS[(code % FIRST_OBJECT_BIT) as usize]);
+		for (i, object) in WorldObject::DEFINED.iter().enumerate() {
+			if code & (FIRST_OBJECT_BIT << i) != 0 {
+				tile.contents.push(*object);
+			}
+		}
+		tile
+	}
+
+	/// Put an evicted chunk's tiles and entities back
+	fn restore(area: &mut Area, chunk: (i32, i32), evicted: &EvictedChunk) {
+		let origin = Coordinate::new(chunk.0 * CHUNK_SIZE, chunk.1 * CHUNK_SIZE);
+		for entity in &evicted.entities {
+			area.entities.insert_json(entity);
+		}
+		let codes = evicted
+			.tiles
+			.chunks(2)
+			.flat_map(|run| std::iter::repeat_n(run[1], run[0] as usize));
+		for (i, code) in codes.enumerate() {
+			let (x, y) = (
+				origin.x + i as i32 / CHUNK_SIZE,
+				origin.y + i as i32 % CHUNK_SIZE,
+			);
+			let mut tile = decode(code);
+			for id in area.entities_at(x, y) {
+				tile.contents.push(WorldObject::ENTITY(*id));
+			}
+			area.set_tile(x, y, tile);
+		}
+	}
+}
@@ -0,0 +1,191 @@
+pub mod definitions {
+	use crate::render::canvas::Color;
+	use crate::world::world::area::WorldObject;
+	use json::JsonValue;
+	use mut_static::MutStatic;
+	use std::collections::HashMap;
+
+	lazy_static! {
+		static ref DEFINITIONS: MutStatic<Definitions> = MutStatic::from(
+			Definitions::from_json(include_str!("../data/objects.json"))
+				.expect("Invalid data/objects.json")
+		);
+	}
+
+	/// The definitions currently in use, initially those in data/objects.json
+	pub fn definitions() -> mut_static::ForceSomeRwLockReadGuard<'static, Definitions> {
+		DEFINITIONS.read().unwrap()
+	}
+
+	/// Ids of the definitions the engine draws map tiles with, so they can't be left out.
+	/// Generators skip anything else they can't find.
+	pub fn required() -> Vec<String> {
+		WorldObject::DEFINED
+			.iter()
+			.map(|object| String::from(object.id()))
+			.collect()
+	}
+
+	/// Replace the definitions in use with ones parsed from `text`, keeping the current ones if it's invalid
+	/// or missing one of the `required` definitions
+	pub fn load_definitions(text: &str) -> Result<(), String> {
+		let definitions = Definitions::from_json(text)?;
+		if let Some(id) = required().iter().find(|id| definitions.get(id).is_none()) {
+			return Err(format!("Missing definition \"{}\"", id));
+		}
+		*DEFINITIONS.write().unwrap() = definitions;
+		Ok(())
+	}
+
+	#[derive(Copy, Clone, PartialEq)]
+	pub enum DefinitionKind {
+		Object,
+		Monster,
+		Item,
+	}
+
+	/// Numbers describing what an object can do. What each means depends on the object's kind.
+	#[derive(Copy, Clone, Default)]
+	pub struct Stats {
+		pub health: i32,
+		pub attack: i32,
+		pub defense: i32,
+	}
+
+	/// How a monster or item turns up in generated levels: how likely it is relative to the rest
+	/// of its kind, how much of the budget it uses, and the first depth it appears at
+	#[derive(Copy, Clone)]
+	pub struct Spawn {
+		pub weight: f64,
+		pub cost: i32,
+		pub min_depth: i32,
+	}
+
+	#[derive(Clone)]
+	pub struct ObjectDefinition {
+		pub id: String,
+		pub kind: DefinitionKind,
+		pub name: String,
+		pub glyph: char,
+		pub fg_color: Color,
+		pub bg_color: Color,
+		pub blocks_movement: bool,
+		pub blocks_sight: bool,
+		/// Radius of the light it gives off, 0 for none
+		pub light: i32,
+		pub stats: Stats,
+		/// None for definitions that aren't spawned when populating levels
+		pub spawn: Option<Spawn>,
+	}
+
+	impl ObjectDefinition {
+		/// Parse a definition, defaulting to a white glyph on no background that blocks nothing
+		fn from_json(id: &str, kind: DefinitionKind, value: &JsonValue) -> Result<Self, String> {
+			let invalid = |field: &str| format!("Invalid {} for \"{}\"", field, id);
+			let color = |field: &str, default: Color| -> Result<Color, String> {
+				if value[field].is_null() {
+					return Ok(default);
+				}
+				value[field]
+					.as_str()
+					.and_then(Color::from_string)
+					.ok_or_else(|| invalid(field))
+			};
+			let flag = |field: &str| -> Result<bool, String> {
+				if value[field].is_null() {
+					return Ok(false);
+				}
+				value[field].as_bool().ok_or_else(|| invalid(field))
+			};
+			let stat = |field: &str| -> Result<i32, String> {
+				if value[field].is_null() {
+					return Ok(0);
+				}
+				value[field].as_i32().ok_or_else(|| invalid(field))
+			};
+			let spawn = &value["spawn"];
+			let spawn = if spawn.is_null() {
+				None
+			} else {
+				let number = |field: &str, default: i32| match &spawn[field] {
+					JsonValue::Null => Some(default),
+					value => value.as_i32().filter(|number| *number >= 1),
+				};
+				Some(Spawn {
+					weight: spawn["weight"]
+						.as_f64()
+						.filter(|weight| *weight > 0.0)
+						.ok_or_else(|| invalid("spawn weight"))?,
+					cost: number("cost", 1).ok_or_else(|| invalid("spawn cost"))?,
+					min_depth: number("min_depth", 1).ok_or_else(|| invalid("spawn min_depth"))?,
+				})
+			};
+			let mut glyph = value["glyph"].as_str().unwrap_or("").chars();
+			Ok(ObjectDefinition {
+				id: String::from(id),
+				kind,
+				name: String::from(value["name"].as_str().unwrap_or(id)),
+				glyph: match (glyph.next(), glyph.next()) {
+					(Some(c), None) => c,
+					_ => return Err(invalid("glyph")),
+				},
+				fg_color: color("fg", Color::White)?,
+				bg_color: color("bg", Color::Black)?,
+				blocks_movement: flag("blocks_movement")?,
+				blocks_sight: flag("blocks_sight")?,
+				light: stat("light")?,
+				stats: Stats {
+					health: stat("health")?,
+					attack: stat("attack")?,
+					defense: stat("defense")?,
+				},
+				spawn,
+			})
+		}
+	}
+
+	/// Every object, monster and item definition, by id
+	pub struct Definitions {
+		pub definitions: HashMap<String, ObjectDefinition>,
+	}
+
+	impl Definitions {
+		/// Parse definitions from a JSON object with "objects", "monsters" and "items" sections,
+		/// each mapping ids to definitions
+		pub fn from_json(text: &str) -> Result<Definitions, String> {
+			let value = json::parse(text).map_err(|e| e.to_string())?;
+			let mut definitions = HashMap::new();
+			for (section, kind) in [
+				("objects", DefinitionKind::Object),
+				("monsters", DefinitionKind::Monster),
+				("items", DefinitionKind::Item),
+			] {
+				for (id, entry) in value[section].entries() {
+					if definitions.contains_key(id) {
+						return Err(format!("Duplicate definition \"{}\"", id));
+					}
+					definitions.insert(
+						String::from(id),
+						ObjectDefinition::from_json(id, kind, entry)?,
+					);
+				}
+			}
+			Ok(Definitions { definitions })
+		}
+
+		pub fn get(&self, id: &str) -> Option<&ObjectDefinition> {
+			self.definitions.get(id)
+		}
+
+		/// Every definition of a kind, sorted by id
+		pub fn of_kind(&self, kind: DefinitionKind) -> Vec<&ObjectDefinition> {
+			let mut definitions: Vec<&ObjectDefinition> = self
+				.definitions
+				.values()
+				.filter(|definition| definition.kind == kind)
+				.collect();
+			definitions.sort_by(|a, b| a.id.cmp(&b.id));
+			definitions
+		}
+	}
+}
@@ -1,9 +1,15 @@
 pub mod dungeon {
 
 	use crate::engine::engine::Coordinate;
-	use crate::world::world::area::Area;
+	use crate::population::population::{populate, PopulationConfig};
+	use crate::world::world::area::{Area, HallwayStyle, Terrain, Tile};
 	use crate::world::world::region::Region;
+	use rand::seq::SliceRandom;
 	use rand::Rng;
+	use std::collections::HashSet;
+
+	/// The definition hung on the walls of rooms as a light source
+	const TORCH: &str = "torch";
 
 	pub struct Dungeon {
 		pub area: Area,
@@ -11,30 +17,311 @@ pub mod dungeon {
 	}
 
 	impl Dungeon {
-		pub fn new(config: DungeonConfig) -> Dungeon {
+		/// Generate a dungeon for the first level down, with monsters and loot from the default
+		/// spawn tables
+		pub fn populated<R: Rng>(config: DungeonConfig, rng: &mut R) -> Result<Dungeon, String> {
+			let mut dungeon = Dungeon::new(config, rng)?;
+			populate(&mut dungeon, &PopulationConfig::default(), 1, rng);
+			Ok(dungeon)
+		}
+
+		/// Generate a dungeon, drawing every random decision from `rng`.
+		/// Fails if the config's room limits leave no size a room could be.
+		pub fn new<R: Rng>(config: DungeonConfig, rng: &mut R) -> Result<Dungeon, String> {
+			let (fallback_width, fallback_height) = config
+				.smallest_room()
+				.ok_or("No room fits the dungeon's size, padding, aspect ratio and area limits")?;
 			let mut dungeon = SubDungeon::new(Region::new(
 				config.dungeon_width,
 				config.dungeon_height,
 				Coordinate::new(0, 0),
 			));
 			let mut area = Area::new(None);
-			dungeon.new_bsp_dungeon_recursive(&mut area, &config, 0, SplitDirection::Random);
-			let rooms = dungeon.get_rooms();
-			let spawn_room = rooms[rand::thread_rng().gen_range(0..rooms.len())];
-			Dungeon {
+			dungeon.new_bsp_dungeon_recursive(&mut area, &config, rng, 0, SplitDirection::Random);
+			// limits that leave no leaf space for a room still get the smallest one in the middle
+			if dungeon.get_rooms().is_empty() {
+				let room = Region::new(
+					fallback_width,
+					fallback_height,
+					Coordinate::new(
+						(config.dungeon_width - fallback_width) / 2,
+						(config.dungeon_height - fallback_height) / 2,
+					),
+				);
+				area.place_region(&room);
+				area.add_room(&room);
+				dungeon.room = Some(room);
+			}
+			let shaped_rooms = dungeon.get_shaped_rooms();
+			let rooms: Vec<&Region> = shaped_rooms.iter().map(|(room, _)| *room).collect();
+			let spawn_index = rng.gen_range(0..rooms.len());
+			let mut exit_index = rng.gen_range(0..rooms.len());
+			while rooms.len() > 1 && exit_index == spawn_index {
+				exit_index = rng.gen_range(0..rooms.len());
+			}
+			for (i, (room, shape)) in shaped_rooms.iter().enumerate() {
+				if i != spawn_index
+					&& i != exit_index
+					&& *shape == RoomShape::Rectangle
+					&& rng.gen_bool(config.terrain_feature_chance)
+				{
+					add_terrain_feature(&mut area, room, rng);
+				}
+			}
+			let exit_point = room_center(rooms[exit_index]);
+			area.set_tile(exit_point.x, exit_point.y, Tile::stairs());
+			for room in &rooms {
+				let torch_point = Coordinate::new(room_center(room).x, room.get_top_y());
+				if rng.gen_bool(config.torch_chance)
+					&& area
+						.get_tile_at(torch_point.x, torch_point.y)
+						.contains_wall()
+					&& area.is_walkable_at(torch_point.x, torch_point.y - 1)
+				{
+					area.spawn(TORCH, torch_point);
+				}
+			}
+			Ok(Dungeon {
 				area,
-				spawn_point: Coordinate::new(
-					spawn_room.position.x + (spawn_room.width/2),
-					spawn_room.position.y + (spawn_room.height/2),
-				),
+				spawn_point: room_center(rooms[spawn_index]),
+			})
+		}
+	}
+
+	/// Cover part of a room's floor in water, lava, rubble or grass.
+	/// A ring of floor is left inside the walls so that every hallway stays connected.
+	fn add_terrain_feature<R: Rng>(area: &mut Area, room: &Region, rng: &mut R) {
+		let (min_x, min_y) = (room.position.x + 2, room.position.y + 2);
+		let (max_x, max_y) = (room.get_edge_x() - 2, room.get_top_y() - 2);
+		if max_x < min_x || max_y < min_y {
+			return;
+		}
+		let terrain = match rng.gen_range(0..4) {
+			0 => Terrain::Water,
+			1 => Terrain::Lava,
+			2 => Terrain::Rubble,
+			_ => Terrain::Grass,
+		};
+		let center_x = (min_x + max_x) as f32 / 2.0;
+		let center_y = (min_y + max_y) as f32 / 2.0;
+		let radius_x = ((max_x - min_x) as f32 / 2.0 + 0.5) * rng.gen_range(0.5..=1.0);
+		let radius_y = ((max_y - min_y) as f32 / 2.0 + 0.5) * rng.gen_range(0.5..=1.0);
+		let density = rng.gen_range(0.2..0.5);
+		for x in min_x..=max_x {
+			for y in min_y..=max_y {
+				let covered = match terrain {
+					Terrain::Water | Terrain::Lava => {
+						let dx = (x as f32 - center_x) / radius_x;
+						let dy = (y as f32 - center_y) / radius_y;
+						dx * dx + dy * dy <= 1.0
+					}
+					_ => rng.gen_bool(density),
+				};
+				if covered && area.get_tile_at(x, y).contents.is_empty() {
+					area.set_tile(x, y, Tile::terrain(terrain));
+				}
 			}
 		}
 	}
 
+	fn room_center(room: &Region) -> Coordinate {
+		room.get_center()
+	}
+
+	fn hallway_style<R: Rng>(config: &DungeonConfig, rng: &mut R) -> HallwayStyle {
+		config
+			.hallway_styles
+			.choose_weighted(rng, |(_, weight)| *weight)
+			.map_or(HallwayStyle::Narrow, |(style, _)| *style)
+	}
+
+	/// The room from each list whose middles are nearest each other
+	fn closest_rooms<'a>(
+		rooms_1: &[&'a Region],
+		rooms_2: &[&'a Region],
+	) -> (&'a Region, &'a Region) {
+		let distance = |(room_1, room_2): &(&Region, &Region)| {
+			let (center_1, center_2) = (room_center(room_1), room_center(room_2));
+			(center_1.x - center_2.x).abs() + (center_1.y - center_2.y).abs()
+		};
+		rooms_1
+			.iter()
+			.flat_map(|room_1| rooms_2.iter().map(move |room_2| (*room_1, *room_2)))
+			.min_by_key(distance)
+			.unwrap()
+	}
+
+	/// Wall in a room's bounds and carve out its floor in a shape.
+	/// Every row and column inside the walls is left with some floor, so that hallways can reach
+	/// the room from any side, and the middle of the room is always floor.
+	fn place_room<R: Rng>(area: &mut Area, room: &Region, shape: RoomShape, rng: &mut R) {
+		if shape == RoomShape::Rectangle {
+			area.place_region(room);
+			return;
+		}
+		area.fill(
+			room.position,
+			Coordinate::new(room.get_edge_x(), room.get_top_y()),
+			Tile::wall(),
+		);
+		let (min_x, min_y) = (room.position.x + 1, room.position.y + 1);
+		let (max_x, max_y) = (room.get_edge_x() - 1, room.get_top_y() - 1);
+		let (width, height) = (max_x - min_x + 1, max_y - min_y + 1);
+		let center = room_center(room);
+		let mut floor: HashSet<(i32, i32)> = HashSet::new();
+		for x in min_x..=max_x {
+			for y in min_y..=max_y {
+				if x == center.x || y == center.y {
+					floor.insert((x, y));
+				}
+			}
+		}
+		match shape {
+			RoomShape::Rectangle => (),
+			RoomShape::Circle => {
+				let (middle_x, middle_y) =
+					((min_x + max_x) as f64 / 2.0, (min_y + max_y) as f64 / 2.0);
+				let (radius_x, radius_y) =
+					((width as f64 + 1.0) / 2.0, (height as f64 + 1.0) / 2.0);
+				for x in min_x..=max_x {
+					for y in min_y..=max_y {
+						let dx = (x as f64 - middle_x) / radius_x;
+						let dy = (y as f64 - middle_y) / radius_y;
+						if dx * dx + dy * dy <= 1.0 {
+							floor.insert((x, y));
+						}
+					}
+				}
+			}
+			RoomShape::Cross => {
+				let (arm_x, arm_y) = ((width / 6).max(1), (height / 6).max(1));
+				for x in min_x..=max_x {
+					for y in min_y..=max_y {
+						if (x - center.x).abs() <= arm_x || (y - center.y).abs() <= arm_y {
+							floor.insert((x, y));
+						}
+					}
+				}
+			}
+			RoomShape::LShape => {
+				let (right, top) = (rng.gen_bool(0.5), rng.gen_bool(0.5));
+				for x in min_x..=max_x {
+					for y in min_y..=max_y {
+						let cut_x = if right {
+							x > center.x + 1
+						} else {
+							x < center.x - 1
+						};
+						let cut_y = if top {
+							y > center.y + 1
+						} else {
+							y < center.y - 1
+						};
+						if !(cut_x && cut_y) {
+							floor.insert((x, y));
+						}
+					}
+				}
+			}
+			RoomShape::PillaredHall => {
+				for x in min_x..=max_x {
+					for y in min_y..=max_y {
+						let inner = x > min_x && x < max_x && y > min_y && y < max_y;
+						let pillar = (x - center.x) % 2 == 0 && (y - center.y) % 2 == 0;
+						if !(inner && pillar) {
+							floor.insert((x, y));
+						}
+					}
+				}
+			}
+			RoomShape::Cave => {
+				// grow out from the middle a tile at a time, so the floor stays in one piece
+				let mut cells: Vec<(i32, i32)> = floor.iter().copied().collect();
+				cells.sort_unstable();
+				let target = (width * height * 3 / 5) as usize;
+				let mut attempts = width * height * 20;
+				while floor.len() < target && attempts > 0 {
+					attempts -= 1;
+					let (x, y) = cells[rng.gen_range(0..cells.len())];
+					let (dx, dy) = [(1, 0), (-1, 0), (0, 1), (0, -1)][rng.gen_range(0..4)];
+					let next = (x + dx, y + dy);
+					if next.0 >= min_x
+						&& next.0 <= max_x && next.1 >= min_y
+						&& next.1 <= max_y && floor.insert(next)
+					{
+						cells.push(next);
+					}
+				}
+			}
+		}
+		for (x, y) in floor {
+			area.set_tile(x, y, Tile::new(None));
+		}
+	}
+
+	/// Bring a room's size within the aspect ratio and area limits, without making it bigger than
+	/// the space it has. None if it can't be brought within them.
+	fn fit_room_size(
+		config: &DungeonConfig,
+		mut width: i32,
+		mut height: i32,
+		space_width: i32,
+		space_height: i32,
+	) -> Option<(i32, i32)> {
+		let ratio = config.max_aspect_ratio.max(1.0);
+		if height as f64 > width as f64 * ratio {
+			height = ((width as f64 * ratio) as i32).max(config.min_room_height);
+		}
+		if width as f64 > height as f64 * ratio {
+			width = ((height as f64 * ratio) as i32).max(config.min_room_width);
+		}
+		let within_ratio =
+			|width: i32, height: i32| width.max(height) as f64 <= width.min(height) as f64 * ratio;
+		// shrink the longer side first and grow the shorter side first, to keep rooms squarish
+		if let Some(max_area) = config.max_room_area {
+			while width * height > max_area {
+				let steps = if width >= height {
+					[(-1, 0), (0, -1)]
+				} else {
+					[(0, -1), (-1, 0)]
+				};
+				match steps.iter().find(|(dw, dh)| {
+					width + dw >= config.min_room_width
+						&& height + dh >= config.min_room_height
+						&& within_ratio(width + dw, height + dh)
+				}) {
+					Some((dw, dh)) => (width, height) = (width + dw, height + dh),
+					None => return None,
+				}
+			}
+		}
+		while width * height < config.min_room_area {
+			let steps = if width <= height {
+				[(1, 0), (0, 1)]
+			} else {
+				[(0, 1), (1, 0)]
+			};
+			match steps.iter().find(|(dw, dh)| {
+				width + dw <= space_width
+					&& height + dh <= space_height
+					&& within_ratio(width + dw, height + dh)
+			}) {
+				Some((dw, dh)) => (width, height) = (width + dw, height + dh),
+				None => return None,
+			}
+		}
+		if within_ratio(width, height) {
+			Some((width, height))
+		} else {
+			None
+		}
+	}
+
 	struct SubDungeon {
 		children: Option<Box<[SubDungeon; 2]>>,
 		region: Region,
 		room: Option<Region>,
+		shape: RoomShape,
 	}
 
 	enum SplitDirection {
@@ -49,26 +336,29 @@ pub mod dungeon {
 				children: None,
 				region,
 				room: None,
+				shape: RoomShape::Rectangle,
 			}
 		}
 
-		fn new_bsp_dungeon_recursive(
+		fn new_bsp_dungeon_recursive<R: Rng>(
 			&mut self,
 			area: &mut Area,
 			config: &DungeonConfig,
+			rng: &mut R,
 			iteration: i8,
 			split_direction: SplitDirection,
 		) {
 			let vertical = match split_direction {
 				SplitDirection::Vertical => true,
 				SplitDirection::Horizontal => false,
-				SplitDirection::Random => rand::random(),
+				SplitDirection::Random => rng.gen(),
 			};
 			let (region_length, min_room_length) = if vertical {
 				(self.region.height, config.min_room_height)
 			} else {
 				(self.region.width, config.min_room_width)
 			};
+			let min_room_length = min_room_length + 2 * config.room_padding;
 			let min_child_length = (region_length as f32
 				* (((100.0 - config.subdungeon_random_split_range as f32) / 2.0) * 0.01))
 				as i32;
@@ -78,6 +368,7 @@ pub mod dungeon {
 						self.new_bsp_dungeon_recursive(
 							area,
 							config,
+							rng,
 							iteration,
 							SplitDirection::Horizontal,
 						);
@@ -85,15 +376,18 @@ pub mod dungeon {
 						self.new_bsp_dungeon_recursive(
 							area,
 							config,
+							rng,
 							iteration,
 							SplitDirection::Vertical,
 						);
 					}
+				} else {
+					// too small to split either way, so it's a leaf of its own
+					self.place_leaf_room(area, config, rng);
 				}
 				return;
 			}
-			let rand = rand::thread_rng()
-				.gen_range(min_child_length + 1..=region_length - min_child_length);
+			let rand = rng.gen_range(min_child_length + 1..=region_length - min_child_length);
 			let new_regions: (Region, Region) = if vertical {
 				(
 					Region::new(self.region.width, rand - 1, self.region.position),
@@ -114,16 +408,8 @@ pub mod dungeon {
 				)
 			};
 			self.children = Some(Box::new([
-				SubDungeon {
-					children: None,
-					region: new_regions.0,
-					room: None,
-				},
-				SubDungeon {
-					children: None,
-					region: new_regions.1,
-					room: None,
-				},
+				SubDungeon::new(new_regions.0),
+				SubDungeon::new(new_regions.1),
 			]));
 			let iteration = iteration + 1;
 			if iteration < config.max_split_iterations {
@@ -133,25 +419,33 @@ pub mod dungeon {
 							children[i].new_bsp_dungeon_recursive(
 								area,
 								config,
+								rng,
 								iteration,
 								SplitDirection::Random,
 							);
 						}
-						if !matches!(children[0].children, None) {
-							let child_2_rooms = children[1].get_rooms();
+						let child_2_rooms = children[1].get_rooms();
+						// a branch whose rooms didn't fit has none to connect to
+						if !children[0].get_rooms().is_empty() && !child_2_rooms.is_empty() {
+							let style = hallway_style(config, rng);
 							let mut hallway_groups = vec![];
 							for room_1 in children[0].get_rooms() {
 								for room_2 in &child_2_rooms {
-									let valid_hallways = area.get_valid_hallways(&room_1, &room_2);
+									let valid_hallways = area
+										.get_valid_styled_hallways(&room_1, &room_2, style, rng);
 									if valid_hallways.len() != 0 {
 										hallway_groups.push(valid_hallways);
 									}
 								}
 							}
-							area.create_hallway_from_valid(
-								&hallway_groups
-									[rand::thread_rng().gen_range(0..hallway_groups.len())],
-							);
+							if hallway_groups.is_empty() {
+								let child_1_rooms = children[0].get_rooms();
+								let (room_1, room_2) =
+									closest_rooms(&child_1_rooms, &child_2_rooms);
+								hallway_groups.push(vec![area.route_hallway(room_1, room_2)]);
+							}
+							let hallway_group = rng.gen_range(0..hallway_groups.len());
+							area.create_hallway_from_valid(&hallway_groups[hallway_group], rng);
 						}
 					}
 					None => (),
@@ -159,6 +453,17 @@ pub mod dungeon {
 			} else {
 				match &mut self.children {
 					Some(children) => {
+						// one of the two leaves always gets a room, so that every branch has one to connect to
+						let mut empty = [false, false];
+						if config.empty_leaf_chance > 0.0 {
+							empty = [
+								rng.gen_bool(config.empty_leaf_chance),
+								rng.gen_bool(config.empty_leaf_chance),
+							];
+							if empty[0] && empty[1] {
+								empty[rng.gen_range(0..2)] = false;
+							}
+						}
 						for i in 0..2 {
 							// FULL REGION DEBUGGING
 							//	children[i].room = Some(Region::new(
@@ -170,61 +475,88 @@ pub mod dungeon {
 							//		),
 							//	));
 
-							let mut width = rand::thread_rng()
-								.gen_range(config.min_room_width..=children[i].region.width);
-							let mut height = rand::thread_rng()
-								.gen_range(config.min_room_height..=children[i].region.height);
-							if height > width * 3 {
-								height = width * 3;
-							}
-							if width > height * 3 {
-								width = height * 3;
+							if !empty[i] {
+								children[i].place_leaf_room(area, config, rng);
 							}
-							let pos_x = if children[i].region.position.x
-								== children[i].region.get_edge_x() - width + 1
-							{
-								children[i].region.position.x
-							} else {
-								rand::thread_rng().gen_range(
-									children[i].region.position.x
-										..=children[i].region.get_edge_x() - width,
-								)
-							};
-							let pos_y = if children[i].region.position.y
-								== children[i].region.get_top_y() - height + 1
-							{
-								children[i].region.position.y
-							} else {
-								rand::thread_rng().gen_range(
-									children[i].region.position.y
-										..=children[i].region.get_top_y() - height,
-								)
-							};
-							children[i].room =
-								Some(Region::new(width, height, Coordinate::new(pos_x, pos_y)));
-							area.place_region(&children[i].room.as_ref().unwrap());
 						}
-						area.create_hallway(
-							&children[0].room.as_ref().unwrap(),
-							&children[1].room.as_ref().unwrap(),
-						);
+						if let (Some(room_1), Some(room_2)) = (&children[0].room, &children[1].room)
+						{
+							let style = hallway_style(config, rng);
+							area.create_styled_hallway(room_1, room_2, style, rng);
+						}
 					}
 					None => (),
 				}
 			}
 		}
 
+		/// Place a room somewhere in the leaf's region, within the padding and size limits,
+		/// unless the limits leave no space for one
+		fn place_leaf_room<R: Rng>(
+			&mut self,
+			area: &mut Area,
+			config: &DungeonConfig,
+			rng: &mut R,
+		) {
+			let padding = config.room_padding;
+			let (min_x, min_y) = (
+				self.region.position.x + padding,
+				self.region.position.y + padding,
+			);
+			let (max_x, max_y) = (
+				self.region.get_edge_x() - padding,
+				self.region.get_top_y() - padding,
+			);
+			let (space_width, space_height) = (max_x - min_x + 1, max_y - min_y + 1);
+			if space_width < config.min_room_width || space_height < config.min_room_height {
+				return;
+			}
+			let width = rng.gen_range(config.min_room_width..=space_width);
+			let height = rng.gen_range(config.min_room_height..=space_height);
+			let (width, height) =
+				match fit_room_size(config, width, height, space_width, space_height) {
+					Some(size) => size,
+					None => return,
+				};
+			let pos_x = if min_x == max_x - width + 1 {
+				min_x
+			} else {
+				rng.gen_range(min_x..=max_x - width)
+			};
+			let pos_y = if min_y == max_y - height + 1 {
+				min_y
+			} else {
+				rng.gen_range(min_y..=max_y - height)
+			};
+			let room = Region::new(width, height, Coordinate::new(pos_x, pos_y));
+			let shape = config
+				.room_shapes
+				.choose_weighted(rng, |(_, weight)| *weight)
+				.map_or(RoomShape::Rectangle, |(shape, _)| *shape);
+			place_room(area, &room, shape, rng);
+			area.add_room(&room);
+			self.room = Some(room);
+			self.shape = shape;
+		}
+
 		fn get_rooms(&self) -> Vec<&Region> {
+			self.get_shaped_rooms()
+				.into_iter()
+				.map(|(room, _)| room)
+				.collect()
+		}
+
+		fn get_shaped_rooms(&self) -> Vec<(&Region, RoomShape)> {
 			let mut rooms = vec![];
 
 			match &self.room {
-				Some(room) => rooms.push(room.clone()),
+				Some(room) => rooms.push((room, self.shape)),
 				None => (),
 			}
 			match &self.children {
 				Some(children) => {
 					for i in 0..2 {
-						let mut vec = children[i].get_rooms();
+						let mut vec = children[i].get_shaped_rooms();
 						rooms.append(&mut vec);
 					}
 				}
@@ -234,17 +566,74 @@ pub mod dungeon {
 		}
 	}
 
+	#[derive(Copy, Clone, PartialEq, Debug)]
+	pub enum RoomShape {
+		Rectangle,
+		/// An ellipse filling the room's bounds
+		Circle,
+		Cross,
+		/// A rectangle with one corner cut away
+		LShape,
+		/// A rectangle with a grid of pillars inside it
+		PillaredHall,
+		/// A ragged blob grown out from the middle
+		Cave,
+	}
+
 	pub struct DungeonConfig {
-		dungeon_width: i32,
-		dungeon_height: i32,
-		subdungeon_random_split_range: i8,
-		max_split_iterations: i8,
-		min_room_width: i32,
-		min_room_height: i32,
+		pub dungeon_width: i32,
+		pub dungeon_height: i32,
+		pub subdungeon_random_split_range: i8,
+		pub max_split_iterations: i8,
+		pub min_room_width: i32,
+		pub min_room_height: i32,
+		/// The probability of a room other than the spawn and exit rooms getting a terrain feature
+		pub terrain_feature_chance: f64,
+		/// The probability of a room getting a torch in the middle of its top wall
+		pub torch_chance: f64,
+		/// The most times longer than it is wide, or wider than it is long, a room can be
+		pub max_aspect_ratio: f64,
+		pub min_room_area: i32,
+		pub max_room_area: Option<i32>,
+		/// The probability of a leaf of the partition being left without a room.
+		/// At most one of each pair of leaves is left empty, so that the rooms still all connect.
+		pub empty_leaf_chance: f64,
+		/// The least space between a room's walls and the edges of the leaf it's in.
+		/// Leaves too small for a room within every limit are left empty, and if that leaves
+		/// none with a room, the smallest room within the limits goes in the middle of the dungeon.
+		pub room_padding: i32,
+		/// The shapes rooms can take, each with how likely it is relative to the others.
+		/// Rooms that aren't rectangles don't get terrain features.
+		pub room_shapes: Vec<(RoomShape, f64)>,
+		/// The styles hallways can be dug in, each with how likely it is relative to the others.
+		/// Hallways are narrow where there's no space for the style picked.
+		pub hallway_styles: Vec<(HallwayStyle, f64)>,
 	}
 
 	impl DungeonConfig {
-		pub fn default() -> DungeonConfig {
+		/// The smallest room size within every limit that fits in the dungeon inside its padding,
+		/// the squarest of them if there are several, or None if there's no such size
+		pub fn smallest_room(&self) -> Option<(i32, i32)> {
+			let ratio = self.max_aspect_ratio.max(1.0);
+			let (space_width, space_height) = (
+				self.dungeon_width - 2 * self.room_padding,
+				self.dungeon_height - 2 * self.room_padding,
+			);
+			(self.min_room_width..=space_width)
+				.flat_map(|width| {
+					(self.min_room_height..=space_height).map(move |height| (width, height))
+				})
+				.filter(|(width, height)| {
+					width * height >= self.min_room_area
+						&& self.max_room_area.is_none_or(|max| width * height <= max)
+						&& *width.max(height) as f64 <= *width.min(height) as f64 * ratio
+				})
+				.min_by_key(|(width, height)| (width * height, (width - height).abs()))
+		}
+	}
+
+	impl Default for DungeonConfig {
+		fn default() -> DungeonConfig {
 			DungeonConfig {
 				dungeon_width: 150,
 				dungeon_height: 50,
@@ -252,6 +641,27 @@ pub mod dungeon {
 				max_split_iterations: 4,
 				min_room_width: 6,
 				min_room_height: 6,
+				terrain_feature_chance: 0.4,
+				torch_chance: 0.5,
+				max_aspect_ratio: 3.0,
+				min_room_area: 0,
+				max_room_area: None,
+				empty_leaf_chance: 0.0,
+				room_padding: 0,
+				room_shapes: vec![
+					(RoomShape::Rectangle, 6.0),
+					(RoomShape::Circle, 1.0),
+					(RoomShape::Cross, 1.0),
+					(RoomShape::LShape, 1.0),
+					(RoomShape::PillaredHall, 1.0),
+					(RoomShape::Cave, 1.0),
+				],
+				hallway_styles: vec![
+					(HallwayStyle::Narrow, 6.0),
+					(HallwayStyle::Wide, 1.0),
+					(HallwayStyle::Diagonal, 1.0),
+					(HallwayStyle::Winding, 1.0),
+				],
 			}
 		}
 	}
@@ -1,60 +1,807 @@
 pub mod engine {
+	use crate::chunks::chunks::ChunkedWorld;
+	use crate::dungeon::dungeon::Dungeon;
+	use crate::entity::store::EntityId;
+	use crate::light::light_map::LightMap;
+	use crate::log::message_log::{MessageCategory, MessageLog};
+	use crate::memory::memory_map::MemoryMap;
+	use crate::overworld::overworld::Overworld;
+	use crate::render::camera::{Camera, CameraMode};
 	use crate::render::canvas::{sort_coordinates, Canvas, Color};
-	use crate::world::world::area::Area;
+	use crate::render::ui::{Layout, Panel, Widget};
+	use crate::render::viewport::Viewport;
+	use crate::world::world::area::{Area, Terrain, WorldObject};
 	use bresenham::Bresenham;
+	use json::{object, JsonValue};
+	use std::collections::{HashMap, HashSet, VecDeque};
 
 	pub struct Player {
 		pub area: Area,
-		pub discovered_area: Area,
+		/// What the player has seen of the area
+		pub memory: MemoryMap,
 		pub location: Coordinate,
 		pub canvas: Canvas,
+		pub layout: Layout,
+		pub turn: u32,
+		pub log: MessageLog,
+		pub show_minimap: bool,
+		pub health: i32,
+		pub max_health: i32,
+		/// How far the player's own torch lights the way
+		pub torch_radius: i32,
+		/// The light on each tile, as of the last time the area was drawn
+		pub light: LightMap,
+		pub camera: Camera,
+		/// Where the map was last drawn on the canvas
+		pub viewport: Viewport,
+		/// The tile being described, under the mouse or the look cursor
+		pub inspecting: Option<Coordinate>,
+		/// Whether the look cursor is being moved with the keyboard
+		pub look_mode: bool,
+		pub activity: Option<Activity>,
+		/// The monsters and items that were in view as of the activity's last step
+		pub in_view: Vec<EntityId>,
+		/// The chunks of an open world, streamed into the area as the player moves
+		pub world: Option<ChunkedWorld>,
+		/// The outdoors and its dungeons, when the game has more than one level
+		pub overworld: Option<Overworld>,
+	}
+
+	impl Player {
+		/// Place a new player at the dungeon's spawn point, with a 100x50 canvas
+		pub fn new(dungeon: Dungeon) -> Player {
+			Player::arrive(dungeon, Event::Entered)
+		}
+
+		/// Place a new player in an open world, generated around them as they explore
+		pub fn in_world(mut world: ChunkedWorld) -> Player {
+			let mut player = Player::arrive(world.dungeon(), Event::SetOut);
+			player.world = Some(world);
+			player
+		}
+
+		/// Place a new player outdoors, with dungeons to enter
+		pub fn outdoors(mut overworld: Overworld) -> Player {
+			let mut player = Player::arrive(overworld.dungeon(), Event::SetOut);
+			player.overworld = Some(overworld);
+			player
+		}
+
+		fn arrive(dungeon: Dungeon, arrival: Event) -> Player {
+			let canvas = Canvas::new(100, 50);
+			let layout = Layout::new(24, 8);
+			let view = layout.viewport(&canvas);
+			let mut player = Player {
+				area: dungeon.area,
+				memory: MemoryMap::new(),
+				location: dungeon.spawn_point,
+				canvas,
+				layout,
+				turn: 0,
+				log: MessageLog::new(100),
+				show_minimap: true,
+				health: 20,
+				max_health: 20,
+				torch_radius: 8,
+				light: LightMap::new(),
+				camera: Camera::new(CameraMode::Centered, dungeon.spawn_point),
+				viewport: Viewport::new(view[0], view[1], dungeon.spawn_point),
+				inspecting: None,
+				look_mode: false,
+				activity: None,
+				in_view: vec![],
+				world: None,
+				overworld: None,
+			};
+			update_lighting(&mut player);
+			emit(&mut player, arrival);
+			player
+		}
+
+		/// Replace the canvas with a blank one of a new size, no smaller than 40x20
+		pub fn resize(&mut self, width: i32, height: i32) {
+			self.canvas = Canvas::new(width.max(40), height.max(20));
+		}
+
+		/// Everything needed to restore the game with `load_state`
+		pub fn save_state(&self) -> JsonValue {
+			let mut state = object!(
+				"area" => self.area.to_json(),
+				"memory" => self.memory.to_json(),
+				"location" => self.location.to_json(),
+				"turn" => self.turn,
+				"health" => self.health,
+				"messages" => self.log.to_json(),
+				"camera" => self.camera.to_json(),
+			);
+			if let Some(world) = &self.world {
+				state["world"] = world.to_json();
+			}
+			if let Some(overworld) = &self.overworld {
+				state["overworld"] = overworld.to_json();
+			}
+			state
+		}
+
+		/// Replace the game with one saved by `save_state`, keeping the current canvas and layout.
+		/// Whatever the player was doing or looking at in the old game is dropped.
+		pub fn load_state(&mut self, state: &JsonValue) -> Result<(), String> {
+			for key in [
+				"area", "memory", "location", "turn", "health", "messages", "camera",
+			] {
+				if state[key].is_null() {
+					return Err(format!("Missing {}", key));
+				}
+			}
+			let area = Area::from_json(&state["area"]).ok_or("Invalid area")?;
+			let memory = MemoryMap::from_json(&state["memory"]).ok_or("Invalid memory")?;
+			let location = Coordinate::from_json(&state["location"]).ok_or("Invalid location")?;
+			let turn = state["turn"].as_u32().ok_or("Invalid turn")?;
+			let health = state["health"].as_i32().ok_or("Invalid health")?;
+			let log = MessageLog::from_json(&state["messages"], self.log.capacity)
+				.ok_or("Invalid messages")?;
+			let camera = Camera::from_json(&state["camera"]).ok_or("Invalid camera")?;
+			let world = match &state["world"] {
+				JsonValue::Null => None,
+				world => Some(ChunkedWorld::from_json(world).ok_or("Invalid world")?),
+			};
+			let overworld = match &state["overworld"] {
+				JsonValue::Null => None,
+				overworld => Some(Overworld::from_json(overworld).ok_or("Invalid overworld")?),
+			};
+			self.area = area;
+			self.memory = memory;
+			self.location = location;
+			self.turn = turn;
+			self.health = health;
+			self.log = log;
+			self.camera = camera;
+			self.world = world;
+			self.overworld = overworld;
+			self.activity = None;
+			self.in_view = vec![];
+			self.inspecting = None;
+			self.look_mode = false;
+			Ok(())
+		}
+	}
+
+	/// Something that happened in the game, reported to the player through the message log
+	pub enum Event {
+		Entered,
+		SetOut,
+		Walked { from: Coordinate, to: Coordinate },
+		Discovered,
+		Blocked { obstacle: String },
+		Hurt { terrain: Terrain, damage: i32 },
+		Died,
+		CameraChanged { mode: CameraMode },
+		Noticed { name: String },
+		Found { name: String },
+		Explored,
+		Descended,
+		Ascended,
+	}
+
+	impl Event {
+		fn message(&self) -> (MessageCategory, String) {
+			match self {
+				Event::Entered => (
+					MessageCategory::Info,
+					String::from("You enter the dungeon."),
+				),
+				Event::SetOut => (
+					MessageCategory::Info,
+					String::from("You set out into the wilderness."),
+				),
+				Event::Walked { from, to } => (
+					MessageCategory::Movement,
+					format!("You walk {}.", compass_direction(*from, *to)),
+				),
+				Event::Discovered => (
+					MessageCategory::Discovery,
+					String::from("You discover new territory."),
+				),
+				Event::Blocked { obstacle } => (
+					MessageCategory::Warning,
+					format!("The {} blocks your way.", obstacle),
+				),
+				Event::Hurt { terrain, damage } => (
+					MessageCategory::Danger,
+					format!("You take {} damage from the {}.", damage, terrain.name()),
+				),
+				Event::Died => (MessageCategory::Danger, String::from("You die.")),
+				Event::Noticed { name } => {
+					(MessageCategory::Warning, format!("You notice a {}.", name))
+				}
+				Event::Explored => (
+					MessageCategory::Info,
+					String::from("There is nothing left to explore."),
+				),
+				Event::Descended => (
+					MessageCategory::Info,
+					String::from("You climb down into the dungeon."),
+				),
+				Event::Ascended => (
+					MessageCategory::Info,
+					String::from("You climb back up to the surface."),
+				),
+				Event::Found { name } => (
+					MessageCategory::Discovery,
+					format!("There is a {} here.", name),
+				),
+				Event::CameraChanged { mode } => {
+					(MessageCategory::Info, format!("Camera: {}.", mode.name()))
+				}
+			}
+		}
+	}
+
+	pub fn emit(player: &mut Player, event: Event) {
+		let (category, text) = event.message();
+		player.log.add(player.turn, category, &text);
+	}
+
+	fn compass_direction(from: Coordinate, to: Coordinate) -> &'static str {
+		let (dx, dy) = (to.x - from.x, to.y - from.y);
+		// slopes steeper than ~2:1 count as orthogonal
+		let horizontal = dx.abs() * 2 > dy.abs();
+		let vertical = dy.abs() * 2 > dx.abs();
+		match (
+			if horizontal { dx.signum() } else { 0 },
+			if vertical { dy.signum() } else { 0 },
+		) {
+			(0, 1) => "north",
+			(1, 1) => "north-east",
+			(1, 0) => "east",
+			(1, -1) => "south-east",
+			(0, -1) => "south",
+			(-1, -1) => "south-west",
+			(-1, 0) => "west",
+			(-1, 1) => "north-west",
+			_ => "in place",
+		}
+	}
+
+	/// Move the player onto an adjacent tile, returning whether they can keep walking
+	fn step_to(player: &mut Player, location: Coordinate) -> bool {
+		let tile = player.area.get_tile_at(location.x, location.y);
+		if !player.area.is_walkable_at(location.x, location.y) {
+			let obstacle = match player.area.blocker_at(location.x, location.y) {
+				Some(id) => player.area.entities.name(id),
+				None => String::from(tile.terrain.name()),
+			};
+			emit(player, Event::Blocked { obstacle });
+			return false;
+		}
+		player.location = location;
+		if let Some(world) = &mut player.world {
+			world.update(&mut player.area, location);
+		}
+		player.turn += tile.terrain.movement_cost();
+		let damage = tile.terrain.damage();
+		if damage > 0 {
+			player.health = (player.health - damage).max(0);
+			emit(
+				player,
+				Event::Hurt {
+					terrain: tile.terrain,
+					damage,
+				},
+			);
+			if player.health == 0 {
+				emit(player, Event::Died);
+				return false;
+			}
+		}
+		true
+	}
+
+	/// The most steps of an activity taken by one action, keeping the frames returned to a manageable number
+	pub const STEPS_PER_ACTION: usize = 40;
+
+	/// Something the player does over several turns, one step per turn
+	pub enum Activity {
+		/// Walk along a path, the next step first
+		Travel { path: VecDeque<Coordinate> },
+		/// Keep walking towards the nearest tile next to unexplored ground,
+		/// skipping tiles already visited in case what's next to them can't be seen
+		Explore { visited: HashSet<(i32, i32)> },
+	}
+
+	/// The result of advancing an activity by a turn
+	pub enum Progress {
+		Continuing,
+		/// Done, with anything to tell the player about it
+		Finished(Option<Event>),
+		/// Stopped early, with anything to tell the player about why
+		Interrupted(Option<Event>),
+	}
+
+	pub fn start_activity(player: &mut Player, activity: Activity) {
+		player.in_view = entities_in_view(player);
+		player.activity = Some(activity);
+	}
+
+	/// Take the next step of the player's activity. It's interrupted when the player takes damage,
+	/// steps on an item or sees a monster or item that wasn't in view before.
+	pub fn tick(player: &mut Player) -> Progress {
+		let step = match &mut player.activity {
+			Some(Activity::Travel { path }) => path.pop_front().ok_or(None),
+			Some(Activity::Explore { visited }) => {
+				visited.insert(player.location.as_tuple());
+				next_explore_step(&player.memory, player.location, visited)
+					.ok_or(Some(Event::Explored))
+			}
+			None => return Progress::Finished(None),
+		};
+		let step = match step {
+			Ok(step) => step,
+			Err(event) => {
+				player.activity = None;
+				return Progress::Finished(event);
+			}
+		};
+		let health = player.health;
+		if !step_to(player, step) {
+			player.activity = None;
+			return Progress::Interrupted(None);
+		}
+		update_lighting(player);
+		let in_view = entities_in_view(player);
+		let noticed = in_view
+			.iter()
+			.find(|id| !player.in_view.contains(id))
+			.copied();
+		player.in_view = in_view;
+		let entities = &player.area.entities;
+		let item_here = player
+			.area
+			.entities_at(player.location.x, player.location.y)
+			.iter()
+			.find(|id| entities.items.contains(id))
+			.copied();
+		let interruption = if player.health < health || passage_at(player, player.location) {
+			Some(None)
+		} else if let Some(id) = item_here {
+			Some(Some(Event::Found {
+				name: entities.name(id),
+			}))
+		} else {
+			noticed.map(|id| {
+				Some(Event::Noticed {
+					name: entities.name(id),
+				})
+			})
+		};
+		let finished = match &player.activity {
+			Some(Activity::Travel { path }) => path.is_empty(),
+			Some(Activity::Explore { .. }) => false,
+			None => true,
+		};
+		match interruption {
+			Some(event) => {
+				player.activity = None;
+				Progress::Interrupted(event)
+			}
+			None if finished => {
+				player.activity = None;
+				Progress::Finished(None)
+			}
+			None => Progress::Continuing,
+		}
+	}
+
+	/// Tick the player's activity until it ends, adding a frame for every step but the last.
+	/// After `STEPS_PER_ACTION` steps it's left unfinished, to be continued by `Action::Resume`.
+	fn run_activity(player: &mut Player, frames: &mut Vec<Canvas>) {
+		let from = player.location;
+		let mut steps = 0;
+		let interruption = loop {
+			if steps == STEPS_PER_ACTION {
+				break None;
+			}
+			steps += 1;
+			match tick(player) {
+				Progress::Continuing => {
+					draw_screen(player);
+					frames.push(player.canvas.clone());
+				}
+				Progress::Finished(event) | Progress::Interrupted(event) => break event,
+			}
+		};
+		if player.location != from {
+			emit(
+				player,
+				Event::Walked {
+					from,
+					to: player.location,
+				},
+			);
+		}
+		if let Some(event) = interruption {
+			emit(player, event);
+		}
+		take_passage(player);
+	}
+
+	/// Whether there's a way between the outdoors and a dungeon at a position
+	fn passage_at(player: &Player, location: Coordinate) -> bool {
+		match &player.overworld {
+			Some(overworld) if overworld.current.is_some() => player
+				.area
+				.get_tile_at(location.x, location.y)
+				.contains_up_stairs(),
+			Some(overworld) => overworld.entrance_at(location).is_some(),
+			None => false,
+		}
+	}
+
+	/// Go down into the dungeon or back up to the outdoors if the player is standing on the way there
+	fn take_passage(player: &mut Player) {
+		if !passage_at(player, player.location) {
+			return;
+		}
+		let overworld = player.overworld.as_mut().unwrap();
+		let event = match overworld.entrance_at(player.location) {
+			Some(index) if overworld.current.is_none() => {
+				player.location = overworld.descend(index, &mut player.area, &mut player.memory);
+				Event::Descended
+			}
+			_ => {
+				let exit = overworld.ascend(&mut player.area, &mut player.memory, player.location);
+				player.location = exit.unwrap_or(player.location);
+				Event::Ascended
+			}
+		};
+		player.inspecting = None;
+		player.camera.center = player.location;
+		emit(player, event);
+	}
+
+	/// The first step on a shortest path to the nearest known, safe tile next to unexplored ground
+	fn next_explore_step(
+		memory: &MemoryMap,
+		location: Coordinate,
+		visited: &HashSet<(i32, i32)>,
+	) -> Option<Coordinate> {
+		let known = |x: i32, y: i32| Coordinate::new(x, y) == location || memory.remembers(x, y);
+		let safe = |x: i32, y: i32| match memory.get(x, y) {
+			Some(memory) => {
+				!memory.wall && memory.terrain.walkable() && memory.terrain.damage() == 0
+			}
+			None => false,
+		};
+		// breadth first search, remembering the first step taken to reach each tile
+		let mut first_steps: HashMap<(i32, i32), Option<Coordinate>> = HashMap::new();
+		let mut queue = VecDeque::from([location]);
+		first_steps.insert(location.as_tuple(), None);
+		while let Some(current) = queue.pop_front() {
+			let first_step = first_steps[&current.as_tuple()];
+			let frontier = (-1..=1)
+				.flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+				.any(|(dx, dy)| !known(current.x + dx, current.y + dy));
+			if frontier && first_step.is_some() && !visited.contains(&current.as_tuple()) {
+				return first_step;
+			}
+			for dx in -1..=1 {
+				for dy in -1..=1 {
+					let next = Coordinate::new(current.x + dx, current.y + dy);
+					if first_steps.contains_key(&next.as_tuple()) || !safe(next.x, next.y) {
+						continue;
+					}
+					first_steps.insert(next.as_tuple(), Some(first_step.unwrap_or(next)));
+					queue.push_back(next);
+				}
+			}
+		}
+		None
+	}
+
+	/// The monsters and items the player can see
+	fn entities_in_view(player: &mut Player) -> Vec<EntityId> {
+		let mut in_view = vec![];
+		for id in player.area.entities.ids() {
+			let entities = &player.area.entities;
+			if !entities.ai.contains_key(&id) && !entities.items.contains(&id) {
+				continue;
+			}
+			match entities.positions.get(&id).copied() {
+				Some(position) if is_visible(player, position) => in_view.push(id),
+				_ => (),
+			}
+		}
+		in_view
+	}
+
+	/// Carry out an action, returning a frame for every step it took
+	pub fn perform(player: &mut Player, action: Action) -> Vec<Canvas> {
+		let mut frames = vec![];
+		let discovered = player.memory.tile_count();
+		match action {
+			Action::Move(coord) if player.health > 0 && can_move_to(player, coord) => {
+				let path: VecDeque<Coordinate> =
+					Bresenham::new(player.location.as_tuple(), coord.as_tuple())
+						.skip(1)
+						.map(|(x, y)| Coordinate::new(x as i32, y as i32))
+						.chain([coord])
+						.collect();
+				start_activity(player, Activity::Travel { path });
+				run_activity(player, &mut frames);
+			}
+			Action::Explore if player.health > 0 => {
+				let visited = HashSet::new();
+				start_activity(player, Activity::Explore { visited });
+				run_activity(player, &mut frames);
+			}
+			Action::Resume if player.activity.is_some() => run_activity(player, &mut frames),
+			Action::ScrollMessages(delta) => player.log.scroll_by(delta),
+			Action::ToggleMinimap => player.show_minimap = !player.show_minimap,
+			Action::CycleCamera => {
+				player.camera.mode = player.camera.mode.next();
+				let mode = player.camera.mode;
+				emit(player, Event::CameraChanged { mode });
+			}
+			Action::Inspect(location) => player.inspecting = location,
+			Action::ToggleLook => {
+				player.look_mode = !player.look_mode;
+				player.inspecting = if player.look_mode {
+					Some(player.location)
+				} else {
+					None
+				};
+			}
+			Action::MoveCursor(dx, dy) if player.look_mode => {
+				if let Some(location) = player.inspecting {
+					player.inspecting = Some(Coordinate::new(location.x + dx, location.y + dy));
+				}
+			}
+			Action::Move(_)
+			| Action::MoveCursor(..)
+			| Action::Explore
+			| Action::Resume
+			| Action::None => (),
+		};
+		draw_screen(player);
+		if player.memory.tile_count() > discovered && player.turn > 0 {
+			emit(player, Event::Discovered);
+			draw_interface(player);
+		}
+		frames.push(player.canvas.clone());
+		frames
+	}
+
+	/// Recompute the light from the player's torch and every entity giving off light
+	pub fn update_lighting(player: &mut Player) {
+		let mut sources = vec![(player.location, player.torch_radius)];
+		for (id, radius) in &player.area.entities.lights {
+			if let Some(position) = player.area.entities.positions.get(id) {
+				sources.push((*position, *radius));
+			}
+		}
+		player.light = LightMap::compute(&player.area, &sources);
+	}
+
+	/// Draw the map into the layout's viewport and the interface panels around it
+	pub fn draw_screen(player: &mut Player) {
+		let viewport = player.layout.viewport(&player.canvas);
+		draw_area(player, viewport[0], viewport[1]);
+		if let Some(screen) = player
+			.inspecting
+			.and_then(|location| player.viewport.to_screen(location))
+		{
+			let unit = player.canvas.get(screen.x, screen.y);
+			player.canvas.set_colored(
+				screen.x,
+				screen.y,
+				unit.character,
+				Color::Black,
+				Color::Yellow,
+				unit.on_click,
+			);
+		}
+		draw_interface(player);
+	}
+
+	pub fn draw_interface(player: &mut Player) {
+		let sidebar = player.layout.sidebar(&player.canvas);
+		let status_bottom = if player.show_minimap || player.inspecting.is_some() {
+			sidebar[1].y - 5
+		} else {
+			sidebar[0].y
+		};
+		Panel::new("Status")
+			.with(Widget::List(vec![(
+				format!("Position: {}, {}", player.location.x, player.location.y),
+				Color::White,
+			)]))
+			.with(Widget::ProgressBar {
+				label: String::from("Health"),
+				value: player.health,
+				max: player.max_health,
+				color: Color::Red,
+			})
+			.with(Widget::ProgressBar {
+				label: String::from("Explored"),
+				value: player.memory.tile_count(),
+				max: player.area.tile_count(),
+				color: Color::Gray,
+			})
+			.with(Widget::List(vec![(
+				String::from("M:map C:camera L:look"),
+				Color::LightGray,
+			)]))
+			.draw(
+				&mut player.canvas,
+				Coordinate::new(sidebar[0].x, status_bottom),
+				sidebar[1],
+			);
+		if let Some(location) = player.inspecting {
+			let description = describe(player, location);
+			Panel::new("Look")
+				.with(Widget::List(description.lines()))
+				.draw(
+					&mut player.canvas,
+					sidebar[0],
+					Coordinate::new(sidebar[1].x, status_bottom - 1),
+				);
+		} else if player.show_minimap {
+			draw_minimap(
+				player,
+				sidebar[0],
+				Coordinate::new(sidebar[1].x, status_bottom - 1),
+			);
+		}
+		draw_message_log(player);
+	}
+
+	/// Draw the player's memory of the map scaled down to fit within the selection, marking the player and stairs
+	fn draw_minimap(player: &mut Player, draw_from: Coordinate, draw_to: Coordinate) {
+		let coords = sort_coordinates(draw_from, draw_to);
+		Panel::new("Minimap").draw(&mut player.canvas, coords[0], coords[1]);
+		let width = coords[1].x - coords[0].x - 1;
+		let height = coords[1].y - coords[0].y - 1;
+		let bounds = match player.memory.bounds() {
+			Some(bounds) => bounds,
+			None => return,
+		};
+		if width <= 0 || height <= 0 {
+			return;
+		}
+		let area_width = bounds[1].x - bounds[0].x + 1;
+		let area_height = bounds[1].y - bounds[0].y + 1;
+		let scale = ((area_width + width - 1) / width)
+			.max((area_height + height - 1) / height)
+			.max(1);
+		let offset_x = (width - (area_width + scale - 1) / scale) / 2;
+		let offset_y = (height - (area_height + scale - 1) / scale) / 2;
+		for map_x in 0..width - offset_x {
+			for map_y in 0..height - offset_y {
+				let (mut floor, mut wall, mut stairs, mut here) = (false, false, false, false);
+				for x in bounds[0].x + map_x * scale..bounds[0].x + (map_x + 1) * scale {
+					for y in bounds[0].y + map_y * scale..bounds[0].y + (map_y + 1) * scale {
+						if Coordinate::new(x, y) == player.location {
+							here = true;
+						}
+						let memory = match player.memory.get(x, y) {
+							Some(memory) => memory,
+							None => continue,
+						};
+						if memory.stairs {
+							stairs = true;
+						}
+						if memory.wall {
+							wall = true;
+						} else {
+							floor = true;
+						}
+					}
+				}
+				let (char, fg_color) = if here {
+					('O', Color::Yellow)
+				} else if stairs {
+					('>', Color::Green)
+				} else {
+					(' ', Color::White)
+				};
+				let bg_color = if floor {
+					Color::Gray
+				} else if wall {
+					Color::LightGray
+				} else {
+					Color::Black
+				};
+				player.canvas.set_colored(
+					coords[0].x + 1 + offset_x + map_x,
+					coords[0].y + 1 + offset_y + map_y,
+					char,
+					fg_color,
+					bg_color,
+					Action::None,
+				);
+			}
+		}
+	}
+
+	fn draw_message_log(player: &mut Player) {
+		let message_area = player.layout.message_area(&player.canvas);
+		let rows = player.layout.message_height - 2;
+		let panel = if player.log.messages.is_empty() {
+			Panel::new("Messages").with(Widget::Text {
+				text: String::from("Click a highlighted tile to move there."),
+				height: rows,
+			})
+		} else {
+			Panel::new("Messages").with(Widget::List(
+				player
+					.log
+					.visible(rows.max(0) as usize)
+					.iter()
+					.map(|message| (message.display(), message.category.color()))
+					.collect(),
+			))
+		};
+		panel.draw(&mut player.canvas, message_area[0], message_area[1]);
+		let right = message_area[1].x;
+		player.canvas.set(
+			right,
+			message_area[1].y - 1,
+			'▲',
+			Color::Black,
+			Action::ScrollMessages(1),
+		);
+		player.canvas.set(
+			right,
+			message_area[0].y + 1,
+			'▼',
+			Color::Black,
+			Action::ScrollMessages(-1),
+		);
 	}
 
 	pub fn draw_area(player: &mut Player, screen_coord_1: Coordinate, screen_coord_2: Coordinate) {
-		let screen_coordinates = sort_coordinates(screen_coord_1, screen_coord_2);
-		let width = screen_coordinates[1].x - screen_coordinates[0].x + 1;
-		let height = screen_coordinates[1].y - screen_coordinates[0].y + 1;
-		let screen_center_x = (width / 2) + screen_coordinates[0].x;
-		let screen_center_y = (height / 2) + screen_coordinates[0].y;
-		for screen_x in screen_coordinates[0].x..screen_coordinates[1].x {
-			for screen_y in screen_coordinates[0].y..screen_coordinates[1].y {
-				let x: i32 = player.location.x - (screen_center_x - screen_x);
-				let y: i32 = player.location.y - (screen_center_y - screen_y);
+		update_lighting(player);
+		let mut viewport = Viewport::new(screen_coord_1, screen_coord_2, player.camera.center);
+		player.camera.follow(
+			player.location,
+			&player.area.rooms,
+			viewport.width(),
+			viewport.height(),
+		);
+		viewport.center = player.camera.center;
+		player.viewport = viewport;
+		for screen_x in viewport.from.x..viewport.to.x {
+			for screen_y in viewport.from.y..viewport.to.y {
+				let Coordinate { x, y } = viewport
+					.to_world(Coordinate::new(screen_x, screen_y))
+					.unwrap();
 				let tile = player.area.get_tile_at(x, y);
 				let visible = is_visible(player, Coordinate::new(x, y));
-				let (char, bg_color) = if Coordinate::new(x, y) == player.location {
-					('O', Some(Color::Gray))
+				let (char, fg_color, bg_color) = if Coordinate::new(x, y) == player.location {
+					('O', Color::White, Some(tile.terrain.bg_color()))
 				} else if visible {
-					player.discovered_area.set_tile(x, y, tile.clone());
-					if tile.contents.len() == 0 {
-						(' ', Some(Color::Gray))
-					} else {
-						(tile.get_char(), tile.get_bgcolor())
-					}
+					player.memory.remember(&player.area, x, y, player.turn);
+					let (char, fg_color, bg_color) = player.area.appearance_at(x, y);
+					let level = player.light.level_at(x, y);
+					(char, fg_color.dimmed(level), Some(bg_color.dimmed(level)))
 				} else {
-					let bgcolor = player.discovered_area.get_tile_at(x, y).get_bgcolor();
-					(
-						player.discovered_area.get_tile_at(x, y).get_char(),
-						if player.discovered_area.tile_exists(x, y) {
-							if matches!(bgcolor, None) {
-								Some(Color::DarkGray)
-							} else {
-								bgcolor
-							}
-						} else {
-							bgcolor
-						},
-					)
+					match player.memory.appearance_at(x, y) {
+						Some((char, fg_color, bg_color)) => (char, fg_color, Some(bg_color)),
+						None => (' ', Color::White, None),
+					}
 				};
-				player.canvas.set(
+				player.canvas.set_colored(
 					screen_x,
 					screen_y,
 					char,
+					fg_color,
 					match bg_color{
 						Some(c) => c,
 						None => Color::Black
 					},
-					if visible && !tile.contains_wall() && Coordinate::new(x, y) != player.location
+					if visible
+						&& player.area.is_walkable_at(x, y)
+						&& Coordinate::new(x, y) != player.location
 					{
 						Action::Move(Coordinate::new(x, y))
 					} else {
@@ -65,12 +812,179 @@ pub mod engine {
 		}
 	}
 
+	/// Draw every tile of an area at its own coordinates as though it were all visible
+	pub fn draw_map(area: &Area, canvas: &mut Canvas) {
+		for x in 0..canvas.width {
+			for y in 0..canvas.height {
+				if !area.tile_exists(x, y) {
+					continue;
+				}
+				let (char, fg_color, bg_color) = area.appearance_at(x, y);
+				canvas.set_colored(x, y, char, fg_color, bg_color, Action::None);
+			}
+		}
+	}
+
+	/// How much the player knows about a tile
+	#[derive(Copy, Clone, PartialEq)]
+	pub enum Visibility {
+		Visible,
+		Remembered { last_seen: u32 },
+		Unknown,
+	}
+
+	/// What the player can tell about a tile
+	pub struct TileDescription {
+		pub location: Coordinate,
+		pub visibility: Visibility,
+		/// None if the tile has never been seen
+		pub terrain: Option<Terrain>,
+		/// Walls, stairs and the like; only what was remembered if it's out of sight
+		pub objects: Vec<String>,
+		/// Empty unless the tile is in sight
+		pub monsters: Vec<String>,
+		/// Empty unless the tile is in sight
+		pub items: Vec<String>,
+	}
+
+	impl TileDescription {
+		/// The description as lines of text for a panel
+		pub fn lines(&self) -> Vec<(String, Color)> {
+			let mut lines = vec![(
+				format!(
+					"{}, {}: {}",
+					self.location.x,
+					self.location.y,
+					match self.visibility {
+						Visibility::Visible => String::from("in sight"),
+						Visibility::Remembered { last_seen } => format!("seen turn {}", last_seen),
+						Visibility::Unknown => String::from("unexplored"),
+					}
+				),
+				Color::LightGray,
+			)];
+			if let Some(terrain) = self.terrain {
+				lines.push((String::from(terrain.name()), Color::White));
+			}
+			for object in &self.objects {
+				lines.push((object.clone(), Color::White));
+			}
+			for monster in &self.monsters {
+				lines.push((monster.clone(), Color::Red));
+			}
+			for item in &self.items {
+				lines.push((item.clone(), Color::Yellow));
+			}
+			lines
+		}
+
+		pub fn to_json(&self) -> JsonValue {
+			let mut value = object!(
+				"location" => self.location.to_json(),
+				"visibility" => match self.visibility {
+					Visibility::Visible => "visible",
+					Visibility::Remembered { .. } => "remembered",
+					Visibility::Unknown => "unknown",
+				},
+				"objects" => self.objects.clone(),
+				"monsters" => self.monsters.clone(),
+				"items" => self.items.clone(),
+			);
+			if let Visibility::Remembered { last_seen } = self.visibility {
+				value["last_seen"] = last_seen.into();
+			}
+			if let Some(terrain) = self.terrain {
+				value["terrain"] = terrain.as_string().into();
+			}
+			value
+		}
+	}
+
+	/// Describe a tile as the player knows it: everything on it if it's in sight,
+	/// or what was remembered of it otherwise
+	pub fn describe(player: &mut Player, location: Coordinate) -> TileDescription {
+		if location == player.location || is_visible(player, location) {
+			let tile = player.area.get_tile_at(location.x, location.y);
+			let mut objects = vec![];
+			for obj in &tile.contents {
+				if !matches!(obj, WorldObject::ENTITY(_)) {
+					objects.push(obj.name());
+				}
+			}
+			let (mut monsters, mut items) = (vec![], vec![]);
+			if location == player.location {
+				monsters.push(WorldObject::PLAYER.name());
+			}
+			let entities = &player.area.entities;
+			for id in player.area.entities_at(location.x, location.y) {
+				match entities.health.get(id) {
+					Some(health) if entities.ai.contains_key(id) => monsters.push(format!(
+						"{} ({}/{})",
+						entities.name(*id),
+						health.current,
+						health.max
+					)),
+					_ if entities.ai.contains_key(id) => monsters.push(entities.name(*id)),
+					_ if entities.items.contains(id) => items.push(entities.name(*id)),
+					_ => objects.push(entities.name(*id)),
+				}
+			}
+			return TileDescription {
+				location,
+				visibility: Visibility::Visible,
+				terrain: Some(tile.terrain),
+				objects,
+				monsters,
+				items,
+			};
+		}
+		match player.memory.get(location.x, location.y) {
+			Some(memory) => {
+				let mut objects = vec![];
+				if memory.wall {
+					objects.push(WorldObject::WALL.name());
+				}
+				if memory.stairs {
+					objects.push(WorldObject::STAIRS.name());
+				}
+				TileDescription {
+					location,
+					visibility: Visibility::Remembered {
+						last_seen: memory.last_seen,
+					},
+					terrain: Some(memory.terrain),
+					objects,
+					monsters: vec![],
+					items: vec![],
+				}
+			}
+			None => TileDescription {
+				location,
+				visibility: Visibility::Unknown,
+				terrain: None,
+				objects: vec![],
+				monsters: vec![],
+				items: vec![],
+			},
+		}
+	}
+
+	/// Whether a move to `location` would be offered by clicking on it
+	fn can_move_to(player: &mut Player, location: Coordinate) -> bool {
+		location != player.location
+			&& player.area.is_walkable_at(location.x, location.y)
+			&& is_visible(player, location)
+	}
+
+	/// Whether a tile is both lit and in the player's line of sight
 	fn is_visible(player: &mut Player, location: Coordinate) -> bool {
-		for (x, y) in Bresenham::new(player.location.as_tuple(), location.as_tuple()) {
+		if !player.light.is_lit(location.x, location.y) {
+			return false;
+		}
+		// the player can see out of the tile they're standing in, even tall grass or forest
+		for (x, y) in Bresenham::new(player.location.as_tuple(), location.as_tuple()).skip(1) {
 			let (x, y) = (x as i32, y as i32);
-			if player.area.get_tile_at(x, y).contents.len() != 0
-				&& Coordinate::new(x, y) != location
-			{
+			if player.area.blocks_sight_at(x, y) && Coordinate::new(x, y) != location {
 				return false;
 			}
 		}
@@ -109,6 +1023,14 @@ pub mod engine {
 				T::try_from(self.y).ok().unwrap(),
 			)
 		}
+
+		pub fn to_json(self) -> JsonValue {
+			json::array![self.x, self.y]
+		}
+
+		pub fn from_json(value: &JsonValue) -> Option<Coordinate> {
+			Some(Coordinate::new(value[0].as_i32()?, value[1].as_i32()?))
+		}
 	}
 
 	impl PartialEq for Coordinate {
@@ -121,5 +1043,20 @@ pub mod engine {
 	pub enum Action {
 		None,
 		Move(Coordinate),
+		/// Scroll the message log, positive being further into the past
+		ScrollMessages(i32),
+		ToggleMinimap,
+		/// Switch to the next camera mode
+		CycleCamera,
+		/// Describe a tile, or stop describing one
+		Inspect(Option<Coordinate>),
+		/// Start or stop moving a look cursor with the keyboard
+		ToggleLook,
+		/// Move the look cursor
+		MoveCursor(i32, i32),
+		/// Walk until there's nothing left to explore or something needs attention
+		Explore,
+		/// Continue an activity that was too long to finish in one action
+		Resume,
 	}
 }
@@ -0,0 +1,354 @@
+pub mod store {
+	use crate::content::definitions::{DefinitionKind, ObjectDefinition};
+	use crate::engine::engine::Coordinate;
+	use crate::render::canvas::Color;
+	use json::{object, JsonValue};
+	use std::collections::{HashMap, HashSet};
+
+	pub type EntityId = u32;
+
+	#[derive(Copy, Clone)]
+	pub struct Renderable {
+		pub glyph: char,
+		pub fg_color: Color,
+		pub bg_color: Color,
+	}
+
+	#[derive(Copy, Clone)]
+	pub struct Health {
+		pub current: i32,
+		pub max: i32,
+	}
+
+	#[derive(Copy, Clone, PartialEq)]
+	pub enum Ai {
+		/// Stays put until something gets its attention
+		Idle,
+		/// Moves around at random
+		Wander,
+		/// Goes after the player
+		Hostile,
+	}
+
+	impl Ai {
+		pub fn as_string(&self) -> String {
+			String::from(match self {
+				Ai::Idle => "idle",
+				Ai::Wander => "wander",
+				Ai::Hostile => "hostile",
+			})
+		}
+
+		pub fn from_string(s: &str) -> Option<Ai> {
+			match s {
+				"idle" => Some(Ai::Idle),
+				"wander" => Some(Ai::Wander),
+				"hostile" => Some(Ai::Hostile),
+				_ => None,
+			}
+		}
+	}
+
+	/// Entities and their components, each component kept in its own map keyed by entity.
+	/// An entity only has the components present for it in those maps.
+	pub struct Entities {
+		next_id: EntityId,
+		/// The id of the definition the entity was created from
+		pub definitions: HashMap<EntityId, String>,
+		pub names: HashMap<EntityId, String>,
+		pub positions: HashMap<EntityId, Coordinate>,
+		pub renderables: HashMap<EntityId, Renderable>,
+		pub blocks_movement: HashSet<EntityId>,
+		pub blocks_sight: HashSet<EntityId>,
+		/// Entities that can be picked up
+		pub items: HashSet<EntityId>,
+		/// Radius of the light the entity gives off
+		pub lights: HashMap<EntityId, i32>,
+		pub health: HashMap<EntityId, Health>,
+		pub ai: HashMap<EntityId, Ai>,
+		pub inventories: HashMap<EntityId, Vec<EntityId>>,
+		/// Every positioned entity, by x then y
+		spatial: HashMap<i32, HashMap<i32, Vec<EntityId>>>,
+	}
+
+	impl Entities {
+		pub fn new() -> Entities {
+			Entities {
+				next_id: 1,
+				definitions: HashMap::new(),
+				names: HashMap::new(),
+				positions: HashMap::new(),
+				renderables: HashMap::new(),
+				blocks_movement: HashSet::new(),
+				blocks_sight: HashSet::new(),
+				items: HashSet::new(),
+				lights: HashMap::new(),
+				health: HashMap::new(),
+				ai: HashMap::new(),
+				inventories: HashMap::new(),
+				spatial: HashMap::new(),
+			}
+		}
+
+		/// Create an entity with no components
+		pub fn create(&mut self) -> EntityId {
+			let id = self.next_id;
+			self.next_id += 1;
+			id
+		}
+
+		/// Create an entity with the components described by a definition.
+		/// Monsters get health, an inventory and hostile AI.
+		pub fn create_from_definition(&mut self, definition: &ObjectDefinition) -> EntityId {
+			let id = self.create();
+			self.definitions.insert(id, definition.id.clone());
+			self.names.insert(id, definition.name.clone());
+			self.renderables.insert(
+				id,
+				Renderable {
+					glyph: definition.glyph,
+					fg_color: definition.fg_color,
+					bg_color: definition.bg_color,
+				},
+			);
+			if definition.blocks_movement {
+				self.blocks_movement.insert(id);
+			}
+			if definition.blocks_sight {
+				self.blocks_sight.insert(id);
+			}
+			if definition.kind == DefinitionKind::Item {
+				self.items.insert(id);
+			}
+			if definition.light > 0 {
+				self.lights.insert(id, definition.light);
+			}
+			if definition.kind == DefinitionKind::Monster {
+				self.health.insert(
+					id,
+					Health {
+						current: definition.stats.health,
+						max: definition.stats.health,
+					},
+				);
+				self.ai.insert(id, Ai::Hostile);
+				self.inventories.insert(id, vec![]);
+			}
+			id
+		}
+
+		/// Every entity that has been created and not removed
+		pub fn ids(&self) -> Vec<EntityId> {
+			let mut ids: Vec<EntityId> = self
+				.definitions
+				.keys()
+				.chain(self.names.keys())
+				.chain(self.positions.keys())
+				.chain(self.renderables.keys())
+				.chain(&self.blocks_movement)
+				.chain(&self.blocks_sight)
+				.chain(&self.items)
+				.chain(self.lights.keys())
+				.chain(self.health.keys())
+				.chain(self.ai.keys())
+				.chain(self.inventories.keys())
+				.copied()
+				.collect();
+			ids.sort_unstable();
+			ids.dedup();
+			ids
+		}
+
+		/// Move an entity, or give it a position if it has none
+		pub fn set_position(&mut self, id: EntityId, position: Coordinate) {
+			self.clear_position(id);
+			self.positions.insert(id, position);
+			self.spatial
+				.entry(position.x)
+				.or_default()
+				.entry(position.y)
+				.or_default()
+				.push(id);
+		}
+
+		/// Take an entity off the map, e.g. when it's picked up
+		pub fn clear_position(&mut self, id: EntityId) {
+			if let Some(old) = self.positions.remove(&id) {
+				if let Some(ids) = self
+					.spatial
+					.get_mut(&old.x)
+					.and_then(|x_col| x_col.get_mut(&old.y))
+				{
+					ids.retain(|other| *other != id);
+				}
+			}
+		}
+
+		/// The entities at a position, in the order they arrived
+		pub fn at<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> &[EntityId] {
+			match self
+				.spatial
+				.get(&x.into())
+				.and_then(|x_col| x_col.get(&y.into()))
+			{
+				Some(ids) => ids,
+				None => &[],
+			}
+		}
+
+		/// Remove an entity and all of its components
+		pub fn remove(&mut self, id: EntityId) {
+			self.clear_position(id);
+			self.definitions.remove(&id);
+			self.names.remove(&id);
+			self.renderables.remove(&id);
+			self.blocks_movement.remove(&id);
+			self.blocks_sight.remove(&id);
+			self.items.remove(&id);
+			self.lights.remove(&id);
+			self.health.remove(&id);
+			self.ai.remove(&id);
+			self.inventories.remove(&id);
+		}
+
+		/// Put an item into a holder's inventory, leaving the map's tiles to `Area::give`
+		pub(crate) fn give(&mut self, holder: EntityId, item: EntityId) {
+			self.clear_position(item);
+			self.inventories.entry(holder).or_default().push(item);
+		}
+
+		pub fn name(&self, id: EntityId) -> String {
+			match self.names.get(&id) {
+				Some(name) => name.clone(),
+				None => String::from("something"),
+			}
+		}
+
+		/// An entity and every component it has
+		pub fn entity_to_json(&self, id: EntityId) -> JsonValue {
+			let mut entity = object!("id" => id);
+			if let Some(definition) = self.definitions.get(&id) {
+				entity["definition"] = definition.clone().into();
+			}
+			if let Some(name) = self.names.get(&id) {
+				entity["name"] = name.clone().into();
+			}
+			if let Some(position) = self.positions.get(&id) {
+				entity["position"] = position.to_json();
+			}
+			if let Some(renderable) = self.renderables.get(&id) {
+				entity["renderable"] = object!(
+					"glyph" => renderable.glyph.to_string(),
+					"fg" => renderable.fg_color.as_string(),
+					"bg" => renderable.bg_color.as_string(),
+				);
+			}
+			if self.blocks_movement.contains(&id) {
+				entity["blocks_movement"] = true.into();
+			}
+			if self.blocks_sight.contains(&id) {
+				entity["blocks_sight"] = true.into();
+			}
+			if self.items.contains(&id) {
+				entity["item"] = true.into();
+			}
+			if let Some(light) = self.lights.get(&id) {
+				entity["light"] = (*light).into();
+			}
+			if let Some(health) = self.health.get(&id) {
+				entity["health"] = json::array![health.current, health.max];
+			}
+			if let Some(ai) = self.ai.get(&id) {
+				entity["ai"] = ai.as_string().into();
+			}
+			if let Some(inventory) = self.inventories.get(&id) {
+				entity["inventory"] = inventory.clone().into();
+			}
+			entity
+		}
+
+		/// Put back an entity as returned by `entity_to_json`, under the id it had
+		pub fn insert_json(&mut self, entity: &JsonValue) -> Option<EntityId> {
+			let id = entity["id"].as_u32()?;
+			if let Some(definition) = entity["definition"].as_str() {
+				self.definitions.insert(id, String::from(definition));
+			}
+			if let Some(name) = entity["name"].as_str() {
+				self.names.insert(id, String::from(name));
+			}
+			if !entity["position"].is_null() {
+				self.set_position(id, Coordinate::from_json(&entity["position"])?);
+			}
+			let renderable = &entity["renderable"];
+			if !renderable.is_null() {
+				self.renderables.insert(
+					id,
+					Renderable {
+						glyph: renderable["glyph"].as_str()?.chars().next()?,
+						fg_color: Color::from_string(renderable["fg"].as_str()?)?,
+						bg_color: Color::from_string(renderable["bg"].as_str()?)?,
+					},
+				);
+			}
+			if entity["blocks_movement"].as_bool() == Some(true) {
+				self.blocks_movement.insert(id);
+			}
+			if entity["blocks_sight"].as_bool() == Some(true) {
+				self.blocks_sight.insert(id);
+			}
+			if entity["item"].as_bool() == Some(true) {
+				self.items.insert(id);
+			}
+			if let Some(light) = entity["light"].as_i32() {
+				self.lights.insert(id, light);
+			}
+			if !entity["health"].is_null() {
+				self.health.insert(
+					id,
+					Health {
+						current: entity["health"][0].as_i32()?,
+						max: entity["health"][1].as_i32()?,
+					},
+				);
+			}
+			if let Some(ai) = entity["ai"].as_str() {
+				self.ai.insert(id, Ai::from_string(ai)?);
+			}
+			if !entity["inventory"].is_null() {
+				let mut inventory = vec![];
+				for item in entity["inventory"].members() {
+					inventory.push(item.as_u32()?);
+				}
+				self.inventories.insert(id, inventory);
+			}
+			Some(id)
+		}
+
+		pub fn to_json(&self) -> JsonValue {
+			let entities: Vec<JsonValue> = self
+				.ids()
+				.into_iter()
+				.map(|id| self.entity_to_json(id))
+				.collect();
+			object!(
+				"next_id" => self.next_id,
+				"entities" => JsonValue::Array(entities),
+			)
+		}
+
+		pub fn from_json(value: &JsonValue) -> Option<Entities> {
+			let mut entities = Entities::new();
+			entities.next_id = value["next_id"].as_u32()?;
+			for entity in value["entities"].members() {
+				entities.insert_json(entity)?;
+			}
+			Some(entities)
+		}
+	}
+
+	impl Default for Entities {
+		fn default() -> Entities {
+			Entities::new()
+		}
+	}
+}
@@ -0,0 +1,80 @@
+pub mod simulation {
+	use crate::dungeon::dungeon::{Dungeon, DungeonConfig};
+	use crate::engine::engine::{perform, Action, Player};
+	use crate::render::canvas::Canvas;
+	use json::JsonValue;
+	use rand::rngs::StdRng;
+	use rand::SeedableRng;
+
+	/// A game driven without wasm or a browser, for scripted play-testing
+	///
+	/// ```
+	/// use roguelike::{Action, Simulation};
+	///
+	/// let mut simulation = Simulation::new(42);
+	/// let spawn = simulation.player.location;
+	/// let outcome = simulation.run(&[Action::Move(spawn), Action::ToggleMinimap]);
+	/// assert!(outcome.screen.contains("Status"));
+	/// ```
+	pub struct Simulation {
+		pub player: Player,
+	}
+
+	/// The result of `Simulation::run`
+	pub struct Outcome {
+		/// The game state as returned by `Player::save_state`
+		pub state: JsonValue,
+		/// The final canvas as text, one line per row from top to bottom
+		pub screen: String,
+		/// The number of frames rendered while performing the actions
+		pub frames: usize,
+	}
+
+	impl Simulation {
+		/// Start a game in a populated dungeon generated from `seed` with the default config
+		pub fn new(seed: u64) -> Simulation {
+			Simulation::with_config(seed, DungeonConfig::default())
+				.expect("Invalid default dungeon config")
+		}
+
+		/// Start a game in a populated dungeon generated from `seed`,
+		/// failing if no room fits the config's limits
+		pub fn with_config(seed: u64, config: DungeonConfig) -> Result<Simulation, String> {
+			let mut rng = StdRng::seed_from_u64(seed);
+			let mut simulation = Simulation {
+				player: Player::new(Dungeon::populated(config, &mut rng)?),
+			};
+			simulation.perform(Action::None);
+			Ok(simulation)
+		}
+
+		/// Perform an action, returning the frames it rendered
+		pub fn perform(&mut self, action: Action) -> Vec<Canvas> {
+			perform(&mut self.player, action)
+		}
+
+		/// Perform whatever action is bound to a screen cell, as if it were clicked
+		pub fn click(&mut self, x: i32, y: i32) -> Vec<Canvas> {
+			let action = self.player.canvas.get(x, y).on_click;
+			self.perform(action)
+		}
+
+		/// Perform every action in order and report the final state
+		pub fn run(&mut self, actions: &[Action]) -> Outcome {
+			let mut frames = 0;
+			for action in actions {
+				frames += self.perform(*action).len();
+			}
+			Outcome {
+				state: self.player.save_state(),
+				screen: self.screen(),
+				frames,
+			}
+		}
+
+		/// The current canvas as text
+		pub fn screen(&self) -> String {
+			self.player.canvas.as_string()
+		}
+	}
+}
@@ -3,43 +3,56 @@ use wasm_bindgen::prelude::*;
 #[macro_use]
 extern crate lazy_static;
 
-mod engine;
-use crate::engine::engine::draw_area;
-use crate::engine::engine::Action;
-use crate::engine::engine::Coordinate;
+pub mod engine;
+pub use crate::engine::engine::Action;
+pub use crate::engine::engine::Coordinate;
 use crate::engine::engine::Player;
+use crate::engine::engine::{describe, perform};
 
-mod world;
-use world::world::area::Area;
+pub mod log;
 
-mod render;
+pub mod content;
+
+pub mod chunks;
+use chunks::chunks::ChunkedWorld;
+pub mod entity;
+pub mod light;
+pub mod memory;
+pub mod noise;
+pub mod overworld;
+use overworld::overworld::Overworld;
+pub mod world;
+
+pub mod render;
 use render::canvas::Canvas;
 use render::canvas::Color;
 
-mod dungeon;
+pub mod dungeon;
 use dungeon::dungeon::Dungeon;
 use dungeon::dungeon::DungeonConfig;
+pub mod maze;
+pub mod population;
+pub mod walker;
+pub mod wfc;
+
+pub mod headless;
+pub use headless::simulation::Simulation;
 
 use mut_static::MutStatic;
 
 use json::object;
 use json::stringify;
 
-use bresenham::Bresenham;
-
 lazy_static! {
-	pub static ref PLAYER: MutStatic<Player> = {
-		let dungeon = Dungeon::new(DungeonConfig::default());
-		MutStatic::from(Player {
-			area: dungeon.area,
-			discovered_area: Area::new(None),
-			location: dungeon.spawn_point,
-			canvas: Canvas::new(100, 50),
-		})
-	};
+	pub static ref PLAYER: MutStatic<Player> = MutStatic::from(Player::new(
+		Dungeon::populated(DungeonConfig::default(), &mut rand::thread_rng())
+			.expect("Invalid default dungeon config")
+	));
 }
 
-fn canvas_vector_to_string(vec: Vec<Canvas>) -> String {
+/// The frames as JSON, along with the message log, a description of any tile being inspected
+/// and whether an activity is waiting to be resumed
+fn canvas_vector_to_string(vec: Vec<Canvas>, player: &mut Player) -> String {
 	let mut canvas_objects = vec![];
 	for canvas in vec {
 		let mut canvas_vec = vec![];
@@ -53,7 +66,10 @@ fn canvas_vector_to_string(vec: Vec<Canvas>) -> String {
 				if !matches!(canvas_unit.bg_color, Color::Black) {
 					obj.insert("bg", canvas_unit.bg_color.as_string()).unwrap();
 				}
-				if matches!(canvas_unit.on_click, Action::Move(_)) {
+				if !matches!(canvas_unit.fg_color, Color::White) {
+					obj.insert("fg", canvas_unit.fg_color.as_string()).unwrap();
+				}
+				if !matches!(canvas_unit.on_click, Action::None) {
 					obj.insert("m", true).unwrap();
 				}
 				x_vec.push(obj);
@@ -62,9 +78,14 @@ fn canvas_vector_to_string(vec: Vec<Canvas>) -> String {
 		}
 		canvas_objects.push(canvas_vec);
 	}
-	let obj = object!(
-		"canvases" => canvas_objects
+	let mut obj = object!(
+		"canvases" => canvas_objects,
+		"messages" => player.log.to_json(),
+		"busy" => player.activity.is_some(),
 	);
+	if let Some(location) = player.inspecting {
+		obj["description"] = describe(player, location).to_json();
+	}
 	stringify(obj)
 }
 
@@ -72,23 +93,133 @@ fn canvas_vector_to_string(vec: Vec<Canvas>) -> String {
 pub fn click(x: i32, y: i32) -> String {
 	let mut player = PLAYER.write().unwrap();
 	let canvas_unit_at_click = player.canvas.get(x, y);
-	let edge = player.canvas.width - 1;
-	let top = player.canvas.height - 1;
-	let mut canvases = vec![];
-	let mut draw = |player: &mut Player| {
-		draw_area(player, Coordinate::new(1, 1), Coordinate::new(edge, top));
-		canvases.push(player.canvas.clone());
+	let canvases = perform(&mut player, canvas_unit_at_click.on_click);
+	canvas_vector_to_string(canvases, &mut player)
+}
+
+/// Scroll the message log, positive being further into the past
+#[wasm_bindgen]
+pub fn scroll_messages(delta: i32) -> String {
+	let mut player = PLAYER.write().unwrap();
+	let canvases = perform(&mut player, Action::ScrollMessages(delta));
+	canvas_vector_to_string(canvases, &mut player)
+}
+
+/// Handle a key press, named as in JavaScript's `KeyboardEvent.key`
+#[wasm_bindgen]
+pub fn key(key: &str) -> String {
+	let mut player = PLAYER.write().unwrap();
+	let action = match key {
+		"m" | "M" => Action::ToggleMinimap,
+		"c" | "C" => Action::CycleCamera,
+		"l" | "L" => Action::ToggleLook,
+		"x" | "X" => Action::Explore,
+		"Escape" if player.look_mode => Action::ToggleLook,
+		"ArrowUp" => Action::MoveCursor(0, 1),
+		"ArrowDown" => Action::MoveCursor(0, -1),
+		"ArrowLeft" => Action::MoveCursor(-1, 0),
+		"ArrowRight" => Action::MoveCursor(1, 0),
+		_ => Action::None,
 	};
-	match canvas_unit_at_click.on_click {
-		Action::Move(coord) => {
-			for (x, y) in Bresenham::new(player.location.as_tuple(), coord.as_tuple()) {
-				player.location = Coordinate::new(x as i32, y as i32);
-				draw(&mut player);
-			}
-			player.location = coord;
-		}
-		_ => (),
+	let canvases = perform(&mut player, action);
+	canvas_vector_to_string(canvases, &mut player)
+}
+
+/// Continue the player's unfinished activity, if any
+#[wasm_bindgen]
+pub fn resume() -> String {
+	let mut player = PLAYER.write().unwrap();
+	let canvases = perform(&mut player, Action::Resume);
+	canvas_vector_to_string(canvases, &mut player)
+}
+
+/// Describe the map tile under a canvas cell, or stop describing when it's outside the map view
+#[wasm_bindgen]
+pub fn hover(x: i32, y: i32) -> String {
+	let mut player = PLAYER.write().unwrap();
+	let location = player.viewport.to_world(Coordinate::new(x, y));
+	let canvases = perform(&mut player, Action::Inspect(location));
+	canvas_vector_to_string(canvases, &mut player)
+}
+
+/// Resize the canvas, in cells, redrawing the whole screen
+#[wasm_bindgen]
+pub fn resize(width: i32, height: i32) -> String {
+	let mut player = PLAYER.write().unwrap();
+	player.resize(width, height);
+	let canvases = perform(&mut player, Action::None);
+	canvas_vector_to_string(canvases, &mut player)
+}
+
+/// The map coordinate drawn in a canvas cell as `[x, y]`, or null if the cell isn't part of the map view
+#[wasm_bindgen]
+pub fn screen_to_world(x: i32, y: i32) -> String {
+	let player = PLAYER.read().unwrap();
+	match player.viewport.to_world(Coordinate::new(x, y)) {
+		Some(coord) => stringify(coord.to_json()),
+		None => stringify(json::JsonValue::Null),
+	}
+}
+
+/// The canvas cell a map coordinate is drawn in as `[x, y]`, or null if it's out of view
+#[wasm_bindgen]
+pub fn world_to_screen(x: i32, y: i32) -> String {
+	let player = PLAYER.read().unwrap();
+	match player.viewport.to_screen(Coordinate::new(x, y)) {
+		Some(coord) => stringify(coord.to_json()),
+		None => stringify(json::JsonValue::Null),
+	}
+}
+
+/// Replace the object, monster and item definitions with ones in the format of data/objects.json,
+/// returning an object with an "error" key if they're invalid
+#[wasm_bindgen]
+pub fn load_definitions(definitions: &str) -> String {
+	match content::definitions::load_definitions(definitions) {
+		Ok(()) => stringify(object!()),
+		Err(error) => stringify(object!("error" => error)),
+	}
+}
+
+/// Replace the game with a new one, either in a "dungeon", "outdoors" with dungeons to enter
+/// or in an open "world" generated as it's explored,
+/// returning the redrawn canvas or an object with an "error" key for any other kind of game
+#[wasm_bindgen]
+pub fn new_game(kind: &str) -> String {
+	let mut game = match kind {
+		"dungeon" => Player::new(
+			Dungeon::populated(DungeonConfig::default(), &mut rand::thread_rng())
+				.expect("Invalid default dungeon config"),
+		),
+		"outdoors" => Player::outdoors(Overworld::new(rand::random())),
+		"world" => Player::in_world(ChunkedWorld::new(rand::random())),
+		_ => return stringify(object!("error" => format!("Unknown kind of game \"{}\"", kind))),
 	};
-	draw(&mut player);
-	canvas_vector_to_string(canvases)
+	let mut player = PLAYER.write().unwrap();
+	game.resize(player.canvas.width, player.canvas.height);
+	*player = game;
+	let canvases = perform(&mut player, Action::None);
+	canvas_vector_to_string(canvases, &mut player)
+}
+
+/// The current game as a JSON string that can be passed to `load`
+#[wasm_bindgen]
+pub fn save() -> String {
+	stringify(PLAYER.read().unwrap().save_state())
+}
+
+/// Restore a game from `save`, returning the redrawn canvas.
+/// The current game is kept if the state is invalid.
+#[wasm_bindgen]
+pub fn load(state: &str) -> String {
+	let mut player = PLAYER.write().unwrap();
+	let result = match json::parse(state) {
+		Ok(value) => player.load_state(&value),
+		Err(_) => Err(String::from("Invalid JSON")),
+	};
+	if let Err(error) = result {
+		return stringify(object!("error" => error));
+	}
+	let canvases = perform(&mut player, Action::None);
+	canvas_vector_to_string(canvases, &mut player)
 }
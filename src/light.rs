@@ -0,0 +1,79 @@
+pub mod light_map {
+	use crate::engine::engine::Coordinate;
+	use crate::world::world::area::Area;
+	use bresenham::Bresenham;
+	use std::collections::HashMap;
+
+	/// How much light reaches each tile, from 0.0 (dark) to 1.0 (fully lit)
+	pub struct LightMap {
+		levels: HashMap<i32, HashMap<i32, f32>>,
+	}
+
+	impl LightMap {
+		pub fn new() -> LightMap {
+			LightMap {
+				levels: HashMap::new(),
+			}
+		}
+
+		/// Light an area from `(position, radius)` sources. Each source fully lights the inner
+		/// half of its radius and fades out towards the edge; overlapping light adds up.
+		pub fn compute(area: &Area, sources: &[(Coordinate, i32)]) -> LightMap {
+			let mut light_map = LightMap::new();
+			for (source, radius) in sources {
+				for x in source.x - radius..=source.x + radius {
+					for y in source.y - radius..=source.y + radius {
+						let distance =
+							(((x - source.x).pow(2) + (y - source.y).pow(2)) as f32).sqrt();
+						if distance > *radius as f32
+							|| !reaches(area, *source, Coordinate::new(x, y))
+						{
+							continue;
+						}
+						let level = (2.0 * (1.0 - distance / (radius + 1) as f32)).min(1.0);
+						light_map.add(x, y, level);
+					}
+				}
+			}
+			light_map
+		}
+
+		fn add(&mut self, x: i32, y: i32, level: f32) {
+			let current = self.levels.entry(x).or_default().entry(y).or_insert(0.0);
+			*current = (*current + level).min(1.0);
+		}
+
+		pub fn level_at<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> f32 {
+			match self
+				.levels
+				.get(&x.into())
+				.and_then(|x_col| x_col.get(&y.into()))
+			{
+				Some(level) => *level,
+				None => 0.0,
+			}
+		}
+
+		pub fn is_lit<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> bool {
+			self.level_at(x, y) > 0.0
+		}
+	}
+
+	impl Default for LightMap {
+		fn default() -> LightMap {
+			LightMap::new()
+		}
+	}
+
+	/// Whether light travels from `source` to `target`; the source and target tiles themselves
+	/// may block sight, so torches in walls shine and lit walls are seen
+	fn reaches(area: &Area, source: Coordinate, target: Coordinate) -> bool {
+		for (x, y) in Bresenham::new(source.as_tuple(), target.as_tuple()) {
+			let (x, y) = (x as i32, y as i32);
+			if Coordinate::new(x, y) != source && area.blocks_sight_at(x, y) {
+				return false;
+			}
+		}
+		true
+	}
+}
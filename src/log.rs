@@ -0,0 +1,155 @@
+pub mod message_log {
+	use crate::render::canvas::Color;
+	use json::{object, JsonValue};
+
+	#[derive(Copy, Clone, PartialEq)]
+	pub enum MessageCategory {
+		Info,
+		Movement,
+		Discovery,
+		Warning,
+		Danger,
+	}
+
+	impl MessageCategory {
+		pub fn color(&self) -> Color {
+			match self {
+				MessageCategory::Info => Color::White,
+				MessageCategory::Movement => Color::LightGray,
+				MessageCategory::Discovery => Color::Green,
+				MessageCategory::Warning => Color::Yellow,
+				MessageCategory::Danger => Color::Red,
+			}
+		}
+
+		pub fn as_string(&self) -> String {
+			String::from(match self {
+				MessageCategory::Info => "info",
+				MessageCategory::Movement => "movement",
+				MessageCategory::Discovery => "discovery",
+				MessageCategory::Warning => "warning",
+				MessageCategory::Danger => "danger",
+			})
+		}
+
+		pub fn from_string(s: &str) -> Option<MessageCategory> {
+			match s {
+				"info" => Some(MessageCategory::Info),
+				"movement" => Some(MessageCategory::Movement),
+				"discovery" => Some(MessageCategory::Discovery),
+				"warning" => Some(MessageCategory::Warning),
+				"danger" => Some(MessageCategory::Danger),
+				_ => None,
+			}
+		}
+	}
+
+	#[derive(Clone)]
+	pub struct Message {
+		/// The turn the message (or its latest repeat) was logged on
+		pub turn: u32,
+		pub text: String,
+		pub category: MessageCategory,
+		/// How many times in a row the message was logged
+		pub count: u32,
+	}
+
+	impl Message {
+		/// The message as it appears in the log, e.g. "[ 12] You walk north. x3"
+		pub fn display(&self) -> String {
+			if self.count > 1 {
+				format!("[{:>3}] {} x{}", self.turn, self.text, self.count)
+			} else {
+				format!("[{:>3}] {}", self.turn, self.text)
+			}
+		}
+
+		pub fn to_json(&self) -> JsonValue {
+			object!(
+				"turn" => self.turn,
+				"text" => self.text.clone(),
+				"category" => self.category.as_string(),
+				"count" => self.count,
+			)
+		}
+
+		pub fn from_json(value: &JsonValue) -> Option<Message> {
+			Some(Message {
+				turn: value["turn"].as_u32()?,
+				text: String::from(value["text"].as_str()?),
+				category: MessageCategory::from_string(value["category"].as_str()?)?,
+				count: value["count"].as_u32()?,
+			})
+		}
+	}
+
+	/// Every message the player has been shown, oldest first
+	pub struct MessageLog {
+		pub messages: Vec<Message>,
+		/// The number of messages kept before the oldest are dropped
+		pub capacity: usize,
+		/// How many messages back from the newest the view is scrolled
+		pub scroll: usize,
+	}
+
+	impl MessageLog {
+		pub fn new(capacity: usize) -> MessageLog {
+			MessageLog {
+				messages: vec![],
+				capacity,
+				scroll: 0,
+			}
+		}
+
+		/// Log a message, collapsing it into the previous one if it's a repeat.
+		/// Scrolls the view back to the newest message.
+		pub fn add(&mut self, turn: u32, category: MessageCategory, text: &str) {
+			self.scroll = 0;
+			if let Some(last) = self.messages.last_mut() {
+				if last.text == text && last.category == category {
+					last.count += 1;
+					last.turn = turn;
+					return;
+				}
+			}
+			self.messages.push(Message {
+				turn,
+				text: String::from(text),
+				category,
+				count: 1,
+			});
+			if self.messages.len() > self.capacity {
+				let excess = self.messages.len() - self.capacity;
+				self.messages.drain(0..excess);
+			}
+		}
+
+		/// Scroll the view by `delta` messages, positive being further into the past
+		pub fn scroll_by(&mut self, delta: i32) {
+			let max_scroll = self.messages.len().saturating_sub(1) as i32;
+			self.scroll = (self.scroll as i32 + delta).clamp(0, max_scroll.max(0)) as usize;
+		}
+
+		/// The `rows` messages visible at the current scroll position, oldest first
+		pub fn visible(&self, rows: usize) -> &[Message] {
+			let end = self.messages.len() - self.scroll.min(self.messages.len());
+			let start = end.saturating_sub(rows);
+			&self.messages[start..end]
+		}
+
+		pub fn to_json(&self) -> JsonValue {
+			JsonValue::Array(self.messages.iter().map(|m| m.to_json()).collect())
+		}
+
+		pub fn from_json(value: &JsonValue, capacity: usize) -> Option<MessageLog> {
+			if !value.is_array() {
+				return None;
+			}
+			let mut log = MessageLog::new(capacity);
+			for message in value.members() {
+				log.messages.push(Message::from_json(message)?);
+			}
+			Some(log)
+		}
+	}
+}
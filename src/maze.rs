@@ -0,0 +1,247 @@
+pub mod maze {
+	use crate::dungeon::dungeon::Dungeon;
+	use crate::engine::engine::Coordinate;
+	use crate::world::world::area::{Area, Tile};
+	use rand::seq::SliceRandom;
+	use rand::Rng;
+	use std::collections::{HashSet, VecDeque};
+
+	const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+	#[derive(Copy, Clone, PartialEq)]
+	pub enum MazeAlgorithm {
+		/// Long winding corridors with few branches
+		RecursiveBacktracker,
+		/// Many short branches and dead ends
+		Prim,
+	}
+
+	pub struct MazeConfig {
+		/// The number of cells across and up the maze
+		pub columns: i32,
+		pub rows: i32,
+		/// How many tiles wide each corridor is, the walls between them being one tile
+		pub corridor_width: i32,
+		pub algorithm: MazeAlgorithm,
+		/// The fraction of dead ends opened up into loops, from 0 (a perfect maze) to 1 (none left)
+		pub braid: f64,
+	}
+
+	impl Default for MazeConfig {
+		fn default() -> MazeConfig {
+			MazeConfig {
+				columns: 30,
+				rows: 15,
+				corridor_width: 1,
+				algorithm: MazeAlgorithm::RecursiveBacktracker,
+				braid: 0.0,
+			}
+		}
+	}
+
+	/// The passages between cells of a maze
+	struct Maze {
+		columns: i32,
+		rows: i32,
+		/// Each passage as the two cells it joins, the smaller first
+		passages: HashSet<((i32, i32), (i32, i32))>,
+	}
+
+	impl Maze {
+		fn contains(&self, cell: (i32, i32)) -> bool {
+			cell.0 >= 0 && cell.1 >= 0 && cell.0 < self.columns && cell.1 < self.rows
+		}
+
+		fn neighbours(&self, cell: (i32, i32)) -> Vec<(i32, i32)> {
+			DIRECTIONS
+				.iter()
+				.map(|(dx, dy)| (cell.0 + dx, cell.1 + dy))
+				.filter(|next| self.contains(*next))
+				.collect()
+		}
+
+		fn connect(&mut self, a: (i32, i32), b: (i32, i32)) {
+			self.passages.insert((a.min(b), a.max(b)));
+		}
+
+		fn connected(&self, a: (i32, i32), b: (i32, i32)) -> bool {
+			self.passages.contains(&(a.min(b), a.max(b)))
+		}
+
+		/// The cells a cell has passages to
+		fn exits(&self, cell: (i32, i32)) -> Vec<(i32, i32)> {
+			self.neighbours(cell)
+				.into_iter()
+				.filter(|next| self.connected(cell, *next))
+				.collect()
+		}
+	}
+
+	/// Generate a maze filling the whole level, with the spawn point and stairs
+	/// as far apart along its corridors as possible
+	pub fn generate<R: Rng>(config: MazeConfig, rng: &mut R) -> Dungeon {
+		let mut maze = Maze {
+			columns: config.columns.max(1),
+			rows: config.rows.max(1),
+			passages: HashSet::new(),
+		};
+		match config.algorithm {
+			MazeAlgorithm::RecursiveBacktracker => carve_backtracker(&mut maze, rng),
+			MazeAlgorithm::Prim => carve_prim(&mut maze, rng),
+		}
+		braid(&mut maze, config.braid, rng);
+
+		let width = config.corridor_width.max(1);
+		// the bottom left tile of a cell's floor
+		let origin =
+			|cell: (i32, i32)| Coordinate::new(1 + cell.0 * (width + 1), 1 + cell.1 * (width + 1));
+		let mut area = Area::new(None);
+		area.fill(
+			Coordinate::new(0, 0),
+			Coordinate::new(maze.columns * (width + 1), maze.rows * (width + 1)),
+			Tile::wall(),
+		);
+		for x in 0..maze.columns {
+			for y in 0..maze.rows {
+				let corner = origin((x, y));
+				area.fill(
+					corner,
+					Coordinate::new(corner.x + width - 1, corner.y + width - 1),
+					Tile::new(None),
+				);
+				// open the wall to the right of or above the cell
+				for (dx, dy) in [(1, 0), (0, 1)] {
+					if maze.connected((x, y), (x + dx, y + dy)) {
+						let start = Coordinate::new(corner.x + dx * width, corner.y + dy * width);
+						area.fill(
+							start,
+							Coordinate::new(
+								start.x + (1 - dx) * (width - 1),
+								start.y + (1 - dy) * (width - 1),
+							),
+							Tile::new(None),
+						);
+					}
+				}
+			}
+		}
+		let (spawn, exit) = furthest_apart(&maze);
+		let centre = |cell: (i32, i32)| {
+			let corner = origin(cell);
+			Coordinate::new(corner.x + (width - 1) / 2, corner.y + (width - 1) / 2)
+		};
+		if spawn != exit {
+			let exit_point = centre(exit);
+			area.set_tile(exit_point.x, exit_point.y, Tile::stairs());
+		}
+		Dungeon {
+			area,
+			spawn_point: centre(spawn),
+		}
+	}
+
+	/// Walk from a random cell, always to an unvisited neighbour, going back when there are none
+	fn carve_backtracker<R: Rng>(maze: &mut Maze, rng: &mut R) {
+		let start = (rng.gen_range(0..maze.columns), rng.gen_range(0..maze.rows));
+		let mut visited = HashSet::from([start]);
+		let mut stack = vec![start];
+		while let Some(cell) = stack.last().copied() {
+			let unvisited: Vec<(i32, i32)> = maze
+				.neighbours(cell)
+				.into_iter()
+				.filter(|next| !visited.contains(next))
+				.collect();
+			match unvisited.choose(rng) {
+				Some(next) => {
+					maze.connect(cell, *next);
+					visited.insert(*next);
+					stack.push(*next);
+				}
+				None => {
+					stack.pop();
+				}
+			}
+		}
+	}
+
+	/// Grow the maze from a random cell, each time joining a random cell next to it
+	fn carve_prim<R: Rng>(maze: &mut Maze, rng: &mut R) {
+		let start = (rng.gen_range(0..maze.columns), rng.gen_range(0..maze.rows));
+		let mut visited = HashSet::from([start]);
+		let mut frontier: Vec<((i32, i32), (i32, i32))> = maze
+			.neighbours(start)
+			.into_iter()
+			.map(|next| (start, next))
+			.collect();
+		while !frontier.is_empty() {
+			let (from, to) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+			if !visited.insert(to) {
+				continue;
+			}
+			maze.connect(from, to);
+			for next in maze.neighbours(to) {
+				if !visited.contains(&next) {
+					frontier.push((to, next));
+				}
+			}
+		}
+	}
+
+	/// Open a passage out of some of the dead ends, preferring to join them to other dead ends
+	fn braid<R: Rng>(maze: &mut Maze, braid: f64, rng: &mut R) {
+		if braid <= 0.0 {
+			return;
+		}
+		let mut dead_ends: Vec<(i32, i32)> = (0..maze.columns)
+			.flat_map(|x| (0..maze.rows).map(move |y| (x, y)))
+			.filter(|cell| maze.exits(*cell).len() == 1)
+			.collect();
+		dead_ends.shuffle(rng);
+		for cell in dead_ends {
+			// an earlier dead end may have been joined to this one
+			if maze.exits(cell).len() != 1 || !rng.gen_bool(braid.min(1.0)) {
+				continue;
+			}
+			let closed: Vec<(i32, i32)> = maze
+				.neighbours(cell)
+				.into_iter()
+				.filter(|next| !maze.connected(cell, *next))
+				.collect();
+			let dead: Vec<(i32, i32)> = closed
+				.iter()
+				.filter(|next| maze.exits(**next).len() == 1)
+				.copied()
+				.collect();
+			let choices = if dead.is_empty() { closed } else { dead };
+			if let Some(next) = choices.choose(rng) {
+				maze.connect(cell, *next);
+			}
+		}
+	}
+
+	/// Two cells far apart, the second being the furthest from the first. In a perfect maze the cell
+	/// furthest from any other is one end of the longest path, so they're as far apart as any two cells.
+	/// Braiding adds loops that make this only an approximation, but searching from every cell instead
+	/// would take time growing with the square of the maze's size.
+	fn furthest_apart(maze: &Maze) -> ((i32, i32), (i32, i32)) {
+		let (start, _) = furthest_from(maze, (0, 0));
+		let (end, _) = furthest_from(maze, start);
+		(start, end)
+	}
+
+	/// The cell with the longest path from `start`, and how long it is
+	fn furthest_from(maze: &Maze, start: (i32, i32)) -> ((i32, i32), usize) {
+		let mut seen = HashSet::from([start]);
+		let mut queue = VecDeque::from([(start, 0)]);
+		let mut furthest = (start, 0);
+		while let Some((cell, distance)) = queue.pop_front() {
+			furthest = (cell, distance);
+			for next in maze.exits(cell) {
+				if seen.insert(next) {
+					queue.push_back((next, distance + 1));
+				}
+			}
+		}
+		furthest
+	}
+}
@@ -0,0 +1,160 @@
+pub mod memory_map {
+	use crate::engine::engine::Coordinate;
+	use crate::render::canvas::Color;
+	use crate::world::world::area::{grid_bounds, Area, Terrain};
+	use json::JsonValue;
+	use std::collections::HashMap;
+
+	/// What the player last saw on a tile
+	#[derive(Copy, Clone)]
+	pub struct Memory {
+		pub glyph: char,
+		pub fg_color: Color,
+		/// The background of whatever was on the tile, None for bare ground
+		pub bg_color: Option<Color>,
+		pub terrain: Terrain,
+		pub wall: bool,
+		pub stairs: bool,
+		/// The turn the tile was last seen on
+		pub last_seen: u32,
+	}
+
+	/// The player's memory of the map. Unlike an `Area` it holds appearances rather than tiles,
+	/// so it shows things as they were when last seen rather than as they are now.
+	pub struct MemoryMap {
+		map: HashMap<i32, HashMap<i32, Memory>>,
+	}
+
+	impl MemoryMap {
+		pub fn new() -> MemoryMap {
+			MemoryMap {
+				map: HashMap::new(),
+			}
+		}
+
+		/// Record how a tile of `area` looks now, leaving out anything that moves on its own
+		pub fn remember(&mut self, area: &Area, x: i32, y: i32, turn: u32) {
+			let tile = area.get_tile_at(x, y);
+			let renderable = area
+				.entities_at(x, y)
+				.iter()
+				.rev()
+				.filter(|id| !area.entities.ai.contains_key(id))
+				.find_map(|id| area.entities.renderables.get(id));
+			let (glyph, fg_color, bg_color) = match renderable {
+				Some(renderable) => (
+					renderable.glyph,
+					renderable.fg_color,
+					match renderable.bg_color {
+						Color::Black => tile.get_bgcolor(),
+						color => Some(color),
+					},
+				),
+				None => (tile.get_char(), tile.get_fgcolor(), tile.get_bgcolor()),
+			};
+			self.map.entry(x).or_default().insert(
+				y,
+				Memory {
+					glyph,
+					fg_color,
+					bg_color,
+					terrain: tile.terrain,
+					wall: tile.contains_wall(),
+					stairs: tile.contains_stairs(),
+					last_seen: turn,
+				},
+			);
+		}
+
+		pub fn get<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> Option<&Memory> {
+			self.map
+				.get(&x.into())
+				.and_then(|x_col| x_col.get(&y.into()))
+		}
+
+		pub fn remembers<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> bool {
+			self.get(x, y).is_some()
+		}
+
+		/// The character, foreground and background color to draw for a remembered tile,
+		/// with bare ground shaded dark gray to set it apart from what's in view
+		pub fn appearance_at<X: Into<i32>, Y: Into<i32>>(
+			&self,
+			x: X,
+			y: Y,
+		) -> Option<(char, Color, Color)> {
+			self.get(x, y).map(|memory| {
+				(
+					memory.glyph,
+					memory.fg_color,
+					memory.bg_color.unwrap_or(Color::DarkGray),
+				)
+			})
+		}
+
+		/// The number of tiles remembered
+		pub fn tile_count(&self) -> i32 {
+			self.map.values().map(|x_col| x_col.len() as i32).sum()
+		}
+
+		/// The bottom left and top right corners of the smallest box containing every remembered tile
+		pub fn bounds(&self) -> Option<[Coordinate; 2]> {
+			grid_bounds(&self.map)
+		}
+
+		/// Every remembered tile as `[x, y, glyph, fg, bg, terrain, wall, stairs, last_seen]`
+		pub fn to_json(&self) -> JsonValue {
+			let mut tiles = vec![];
+			for (x, x_col) in &self.map {
+				for (y, memory) in x_col {
+					tiles.push(json::array![
+						*x,
+						*y,
+						memory.glyph.to_string(),
+						memory.fg_color.as_string(),
+						match memory.bg_color {
+							Some(color) => JsonValue::from(color.as_string()),
+							None => JsonValue::Null,
+						},
+						memory.terrain.as_string(),
+						memory.wall,
+						memory.stairs,
+						memory.last_seen,
+					]);
+				}
+			}
+			JsonValue::Array(tiles)
+		}
+
+		pub fn from_json(value: &JsonValue) -> Option<MemoryMap> {
+			let mut memory_map = MemoryMap::new();
+			for entry in value.members() {
+				let memory = Memory {
+					glyph: entry[2].as_str()?.chars().next()?,
+					fg_color: Color::from_string(entry[3].as_str()?)?,
+					bg_color: if entry[4].is_null() {
+						None
+					} else {
+						Some(Color::from_string(entry[4].as_str()?)?)
+					},
+					terrain: Terrain::from_string(entry[5].as_str()?)?,
+					wall: entry[6].as_bool()?,
+					stairs: entry[7].as_bool()?,
+					last_seen: entry[8].as_u32()?,
+				};
+				memory_map
+					.map
+					.entry(entry[0].as_i32()?)
+					.or_default()
+					.insert(entry[1].as_i32()?, memory);
+			}
+			Some(memory_map)
+		}
+	}
+
+	impl Default for MemoryMap {
+		fn default() -> MemoryMap {
+			MemoryMap::new()
+		}
+	}
+}
@@ -0,0 +1,25 @@
+pub mod value_noise {
+	/// A well mixed number from a seed and a position
+	pub fn hash(seed: u64, x: i32, y: i32) -> u64 {
+		let mut h = seed
+			^ (x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+			^ (y as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+		h ^= h >> 33;
+		h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+		h ^= h >> 33;
+		h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+		h ^ (h >> 33)
+	}
+
+	/// Smooth value noise from 0 to 1, varying over roughly `scale` tiles
+	pub fn noise(seed: u64, x: i32, y: i32, scale: f64) -> f64 {
+		let (fx, fy) = (x as f64 / scale, y as f64 / scale);
+		let (x0, y0) = (fx.floor() as i32, fy.floor() as i32);
+		let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+		let (tx, ty) = (smooth(fx - x0 as f64), smooth(fy - y0 as f64));
+		let corner = |x: i32, y: i32| (hash(seed, x, y) >> 11) as f64 / (1u64 << 53) as f64;
+		let bottom = corner(x0, y0) * (1.0 - tx) + corner(x0 + 1, y0) * tx;
+		let top = corner(x0, y0 + 1) * (1.0 - tx) + corner(x0 + 1, y0 + 1) * tx;
+		bottom * (1.0 - ty) + top * ty
+	}
+}
@@ -0,0 +1,311 @@
+pub mod overworld {
+	use crate::dungeon::dungeon::{Dungeon, DungeonConfig};
+	use crate::engine::engine::Coordinate;
+	use crate::maze::maze::{self, MazeConfig};
+	use crate::memory::memory_map::MemoryMap;
+	use crate::noise::value_noise::{hash, noise};
+	use crate::population::population::{self, PopulationConfig};
+	use crate::walker::walker::{self, WalkerConfig};
+	use crate::wfc::wfc::{self, WfcConfig, ROOMS_SAMPLE};
+	use crate::world::world::area::{Area, Terrain, Tile};
+	use json::{object, JsonValue};
+	use rand::rngs::StdRng;
+	use rand::{Rng, SeedableRng};
+	use std::collections::{HashMap, HashSet, VecDeque};
+
+	/// The outdoors, with entrances to dungeons that are generated the first time they're entered
+	/// and kept as they were left after that
+	pub struct Overworld {
+		pub seed: u64,
+		pub width: i32,
+		pub height: i32,
+		/// How many dungeon entrances to place
+		pub dungeon_count: usize,
+		/// Where each dungeon is entered from, in the order of the dungeons.
+		/// Each leads one level deeper than the one before, so later dungeons are more dangerous.
+		pub entrances: Vec<Coordinate>,
+		/// The dungeon the player is in, or None when they're outdoors
+		pub current: Option<usize>,
+		/// The outdoors and the player's memory of it, while they're in a dungeon
+		surface: Option<(Area, MemoryMap)>,
+		/// Dungeons the player has left, with their memory of each and where its up staircase is
+		dungeons: HashMap<usize, (Area, MemoryMap, Coordinate)>,
+	}
+
+	impl Overworld {
+		pub fn new(seed: u64) -> Overworld {
+			Overworld {
+				seed,
+				width: 120,
+				height: 60,
+				dungeon_count: 4,
+				entrances: vec![],
+				current: None,
+				surface: None,
+				dungeons: HashMap::new(),
+			}
+		}
+
+		/// Generate the outdoors: meadows and forest between lakes and mountains, ringed by mountains,
+		/// with the dungeon entrances spread out across the ground reachable from the spawn point
+		pub fn dungeon(&mut self) -> Dungeon {
+			let mut area = Area::new(None);
+			for x in 0..self.width {
+				for y in 0..self.height {
+					area.set_tile(x, y, Tile::terrain(self.terrain_at(x, y)));
+				}
+			}
+			let center = Coordinate::new(self.width / 2, self.height / 2);
+			let spawn_point = nearest_open(&area, center).unwrap_or(center);
+			area.set_tile(spawn_point.x, spawn_point.y, Tile::terrain(Terrain::Meadow));
+
+			let mut rng = StdRng::seed_from_u64(hash(self.seed, -1, -1));
+			let mut candidates: Vec<(Coordinate, i32)> = reachable(&area, spawn_point)
+				.into_iter()
+				.filter(|(position, _)| {
+					area.get_tile_at(position.x, position.y).terrain == Terrain::Meadow
+				})
+				.collect();
+			candidates.sort_unstable_by_key(|(position, _)| position.as_tuple::<i32>());
+			self.entrances = vec![];
+			// the minimum spacing is relaxed until every entrance fits
+			let mut spacing = (self.width.min(self.height) / 3).max(1);
+			while self.entrances.len() < self.dungeon_count && spacing > 0 {
+				let far_enough: Vec<Coordinate> = candidates
+					.iter()
+					.filter(|(position, distance)| {
+						*distance >= spacing
+							&& self.entrances.iter().all(|entrance| {
+								(entrance.x - position.x)
+									.abs()
+									.max((entrance.y - position.y).abs())
+									>= spacing
+							})
+					})
+					.map(|(position, _)| *position)
+					.collect();
+				match far_enough.len() {
+					0 => spacing -= 1,
+					len => self.entrances.push(far_enough[rng.gen_range(0..len)]),
+				}
+			}
+			for entrance in &self.entrances {
+				area.set_tile(entrance.x, entrance.y, Tile::stairs());
+			}
+			Dungeon { area, spawn_point }
+		}
+
+		fn terrain_at(&self, x: i32, y: i32) -> Terrain {
+			if x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1 {
+				return Terrain::Mountain;
+			}
+			let height =
+				0.65 * noise(self.seed, x, y, 16.0) + 0.35 * noise(self.seed + 1, x, y, 5.0);
+			let moisture = noise(self.seed + 2, x, y, 10.0);
+			if height < 0.3 {
+				Terrain::Water
+			} else if height > 0.7 {
+				Terrain::Mountain
+			} else if moisture > 0.58 {
+				Terrain::Forest
+			} else {
+				Terrain::Meadow
+			}
+		}
+
+		/// The dungeon entered from a position, if there's an entrance there
+		pub fn entrance_at(&self, position: Coordinate) -> Option<usize> {
+			self.entrances
+				.iter()
+				.position(|entrance| *entrance == position)
+		}
+
+		/// Swap the outdoors for a dungeon, returning where the player arrives
+		pub fn descend(
+			&mut self,
+			index: usize,
+			area: &mut Area,
+			memory: &mut MemoryMap,
+		) -> Coordinate {
+			let (dungeon_area, dungeon_memory, arrival) = match self.dungeons.remove(&index) {
+				Some(dungeon) => dungeon,
+				None => {
+					let dungeon = self.generate_dungeon(index);
+					(dungeon.area, MemoryMap::new(), dungeon.spawn_point)
+				}
+			};
+			let surface_area = std::mem::replace(area, dungeon_area);
+			let surface_memory = std::mem::replace(memory, dungeon_memory);
+			self.surface = Some((surface_area, surface_memory));
+			self.current = Some(index);
+			arrival
+		}
+
+		/// Swap the current dungeon back for the outdoors, returning the entrance the player comes out of.
+		/// `location` is where the player left the dungeon from.
+		pub fn ascend(
+			&mut self,
+			area: &mut Area,
+			memory: &mut MemoryMap,
+			location: Coordinate,
+		) -> Option<Coordinate> {
+			let index = self.current?;
+			let (surface_area, surface_memory) = self.surface.take()?;
+			let dungeon_area = std::mem::replace(area, surface_area);
+			let dungeon_memory = std::mem::replace(memory, surface_memory);
+			self.dungeons
+				.insert(index, (dungeon_area, dungeon_memory, location));
+			self.current = None;
+			Some(self.entrances[index])
+		}
+
+		/// How deep the dungeon at an entrance is, from 1 for the first
+		pub fn depth(index: usize) -> i32 {
+			index as i32 + 1
+		}
+
+		/// A dungeon made by one of the generators, taking turns between them,
+		/// with an up staircase where the player arrives and monsters and loot for its depth
+		fn generate_dungeon(&self, index: usize) -> Dungeon {
+			let mut rng = StdRng::seed_from_u64(hash(self.seed, index as i32, 0));
+			let bsp = |rng: &mut StdRng| {
+				let config = DungeonConfig {
+					dungeon_width: 80,
+					dungeon_height: 40,
+					max_split_iterations: 3,
+					..Default::default()
+				};
+				Dungeon::new(config, rng).expect("Invalid overworld dungeon config")
+			};
+			let mut dungeon = match index % 4 {
+				0 => bsp(&mut rng),
+				1 => walker::generate(WalkerConfig::default(), &mut rng),
+				2 => {
+					let config = MazeConfig {
+						braid: 0.3,
+						..Default::default()
+					};
+					maze::generate(config, &mut rng)
+				}
+				_ => match wfc::generate(ROOMS_SAMPLE, WfcConfig::default(), &mut rng) {
+					Ok(dungeon) => dungeon,
+					Err(_) => bsp(&mut rng),
+				},
+			};
+			let arrival = dungeon.spawn_point;
+			dungeon
+				.area
+				.set_tile(arrival.x, arrival.y, Tile::up_stairs());
+			let config = PopulationConfig::default();
+			population::populate(&mut dungeon, &config, Overworld::depth(index), &mut rng);
+			dungeon
+		}
+
+		pub fn to_json(&self) -> JsonValue {
+			let mut dungeons: Vec<&usize> = self.dungeons.keys().collect();
+			dungeons.sort_unstable();
+			let dungeons: Vec<JsonValue> = dungeons
+				.into_iter()
+				.map(|index| {
+					let (area, memory, exit) = &self.dungeons[index];
+					object!(
+						"index" => *index,
+						"area" => area.to_json(),
+						"memory" => memory.to_json(),
+						"exit" => exit.to_json(),
+					)
+				})
+				.collect();
+			let entrances: Vec<JsonValue> = self
+				.entrances
+				.iter()
+				.map(|entrance| entrance.to_json())
+				.collect();
+			let mut value = object!(
+				"seed" => self.seed.to_string(),
+				"width" => self.width,
+				"height" => self.height,
+				"entrances" => entrances,
+				"dungeons" => dungeons,
+			);
+			if let (Some(index), Some((area, memory))) = (self.current, &self.surface) {
+				value["current"] = index.into();
+				value["surface"] = object!(
+					"area" => area.to_json(),
+					"memory" => memory.to_json(),
+				);
+			}
+			value
+		}
+
+		pub fn from_json(value: &JsonValue) -> Option<Overworld> {
+			let mut overworld = Overworld::new(value["seed"].as_str()?.parse().ok()?);
+			overworld.width = value["width"].as_i32()?;
+			overworld.height = value["height"].as_i32()?;
+			for entrance in value["entrances"].members() {
+				overworld.entrances.push(Coordinate::from_json(entrance)?);
+			}
+			overworld.dungeon_count = overworld.entrances.len();
+			for dungeon in value["dungeons"].members() {
+				overworld.dungeons.insert(
+					dungeon["index"].as_usize()?,
+					(
+						Area::from_json(&dungeon["area"])?,
+						MemoryMap::from_json(&dungeon["memory"])?,
+						Coordinate::from_json(&dungeon["exit"])?,
+					),
+				);
+			}
+			if let Some(index) = value["current"].as_usize() {
+				overworld.current = Some(index);
+				overworld.surface = Some((
+					Area::from_json(&value["surface"]["area"])?,
+					MemoryMap::from_json(&value["surface"]["memory"])?,
+				));
+			}
+			Some(overworld)
+		}
+	}
+
+	/// The nearest walkable tile to `center` with walkable ground all around it
+	fn nearest_open(area: &Area, center: Coordinate) -> Option<Coordinate> {
+		let open = |x: i32, y: i32| {
+			(-1..=1).all(|dx| (-1..=1).all(|dy| area.is_walkable_at(x + dx, y + dy)))
+		};
+		let [min, max] = area.bounds()?;
+		let radius_limit = (max.x - min.x).max(max.y - min.y);
+		for radius in 0..=radius_limit {
+			for x in center.x - radius..=center.x + radius {
+				for y in center.y - radius..=center.y + radius {
+					let on_ring = (x - center.x).abs() == radius || (y - center.y).abs() == radius;
+					if on_ring && area.tile_exists(x, y) && open(x, y) {
+						return Some(Coordinate::new(x, y));
+					}
+				}
+			}
+		}
+		None
+	}
+
+	/// Every tile that can be walked to from `start`, with the number of steps it takes
+	fn reachable(area: &Area, start: Coordinate) -> Vec<(Coordinate, i32)> {
+		let mut seen = HashSet::from([start.as_tuple::<i32>()]);
+		let mut queue = VecDeque::from([(start, 0)]);
+		let mut tiles = vec![];
+		while let Some((position, distance)) = queue.pop_front() {
+			tiles.push((position, distance));
+			for dx in -1..=1 {
+				for dy in -1..=1 {
+					let next = Coordinate::new(position.x + dx, position.y + dy);
+					if area.tile_exists(next.x, next.y)
+						&& area.is_walkable_at(next.x, next.y)
+						&& seen.insert(next.as_tuple())
+					{
+						queue.push_back((next, distance + 1));
+					}
+				}
+			}
+		}
+		tiles
+	}
+}
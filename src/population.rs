@@ -0,0 +1,176 @@
+pub mod population {
+	use crate::content::definitions::{definitions, DefinitionKind};
+	use crate::dungeon::dungeon::Dungeon;
+	use crate::engine::engine::Coordinate;
+	use crate::entity::store::EntityId;
+	use rand::seq::SliceRandom;
+	use rand::Rng;
+	use std::collections::HashSet;
+
+	/// Something that can be spawned, with how likely it is relative to the rest of its table,
+	/// how much of the budget it uses, and the first depth it appears at
+	#[derive(Clone)]
+	pub struct SpawnEntry {
+		pub definition: String,
+		pub weight: f64,
+		pub cost: i32,
+		pub min_depth: i32,
+	}
+
+	impl SpawnEntry {
+		pub fn new(definition: &str, weight: f64, cost: i32, min_depth: i32) -> SpawnEntry {
+			SpawnEntry {
+				definition: String::from(definition),
+				weight,
+				cost,
+				min_depth,
+			}
+		}
+	}
+
+	pub struct PopulationConfig {
+		pub monsters: Vec<SpawnEntry>,
+		pub items: Vec<SpawnEntry>,
+		/// The budget spent on monsters on the first level, and how much it grows each level down
+		pub monster_budget: i32,
+		pub monster_budget_per_depth: i32,
+		pub item_budget: i32,
+		pub item_budget_per_depth: i32,
+		/// How far from the spawn point nothing is placed, besides the room the spawn point is in
+		pub spawn_clearance: i32,
+	}
+
+	/// Every loaded definition of a kind with a spawn block, cheapest first
+	fn spawn_table(kind: DefinitionKind) -> Vec<SpawnEntry> {
+		let mut table: Vec<SpawnEntry> = definitions()
+			.of_kind(kind)
+			.into_iter()
+			.filter_map(|definition| {
+				let spawn = definition.spawn?;
+				Some(SpawnEntry::new(
+					&definition.id,
+					spawn.weight,
+					spawn.cost,
+					spawn.min_depth,
+				))
+			})
+			.collect();
+		// of_kind sorts by id, which breaks ties in cost
+		table.sort_by_key(|entry| entry.cost);
+		table
+	}
+
+	impl Default for PopulationConfig {
+		/// Spawn tables made of the loaded monster and item definitions
+		fn default() -> PopulationConfig {
+			PopulationConfig {
+				monsters: spawn_table(DefinitionKind::Monster),
+				items: spawn_table(DefinitionKind::Item),
+				monster_budget: 4,
+				monster_budget_per_depth: 3,
+				item_budget: 3,
+				item_budget_per_depth: 1,
+				spawn_clearance: 5,
+			}
+		}
+	}
+
+	/// Something placed by `populate`
+	pub struct Placement {
+		pub entity: EntityId,
+		pub definition: String,
+		pub position: Coordinate,
+		pub cost: i32,
+	}
+
+	/// Spend the budgets for a depth on monsters and items from the spawn tables, placing each on
+	/// a random empty floor tile away from the spawn point. Spending stops when nothing left in a
+	/// table is affordable or there's nowhere left to put it. Entries without a loaded definition
+	/// are skipped without spending anything on them.
+	pub fn populate<R: Rng>(
+		dungeon: &mut Dungeon,
+		config: &PopulationConfig,
+		depth: i32,
+		rng: &mut R,
+	) -> Vec<Placement> {
+		let mut free = free_tiles(dungeon, config.spawn_clearance);
+		let mut placements = vec![];
+		for (table, budget) in [
+			(
+				&config.monsters,
+				config.monster_budget + config.monster_budget_per_depth * (depth - 1),
+			),
+			(
+				&config.items,
+				config.item_budget + config.item_budget_per_depth * (depth - 1),
+			),
+		] {
+			let mut remaining = budget;
+			let mut undefined = HashSet::new();
+			while !free.is_empty() {
+				let affordable: Vec<&SpawnEntry> = table
+					.iter()
+					.filter(|entry| {
+						entry.min_depth <= depth
+							&& entry.cost <= remaining
+							&& !undefined.contains(&entry.definition)
+					})
+					.collect();
+				let entry = match affordable.choose_weighted(rng, |entry| entry.weight) {
+					Ok(entry) => *entry,
+					Err(_) => break,
+				};
+				let position = free.swap_remove(rng.gen_range(0..free.len()));
+				match dungeon.area.spawn(&entry.definition, position) {
+					Some(entity) => {
+						placements.push(Placement {
+							entity,
+							definition: entry.definition.clone(),
+							position,
+							cost: entry.cost,
+						});
+						remaining -= entry.cost.max(1);
+					}
+					None => {
+						free.push(position);
+						undefined.insert(entry.definition.clone());
+					}
+				}
+			}
+		}
+		placements
+	}
+
+	/// Every empty floor tile that's safe to stand on and outside the spawn point's room and clearance,
+	/// in order of position
+	fn free_tiles(dungeon: &Dungeon, clearance: i32) -> Vec<Coordinate> {
+		let area = &dungeon.area;
+		let spawn = dungeon.spawn_point;
+		let spawn_rooms: Vec<&[Coordinate; 2]> = area
+			.rooms
+			.iter()
+			.filter(|[min, max]| {
+				spawn.x >= min.x && spawn.x <= max.x && spawn.y >= min.y && spawn.y <= max.y
+			})
+			.collect();
+		let mut tiles = vec![];
+		for (x, x_col) in &area.map {
+			for (y, tile) in x_col {
+				let (x, y) = (*x, *y);
+				let near_spawn = (x - spawn.x).abs().max((y - spawn.y).abs()) <= clearance
+					|| spawn_rooms
+						.iter()
+						.any(|[min, max]| x >= min.x && x <= max.x && y >= min.y && y <= max.y);
+				if !near_spawn
+					&& tile.contents.is_empty()
+					&& tile.is_walkable()
+					&& tile.terrain.damage() == 0
+				{
+					tiles.push(Coordinate::new(x, y));
+				}
+			}
+		}
+		tiles.sort_unstable_by_key(|position| position.as_tuple::<i32>());
+		tiles
+	}
+}
@@ -11,7 +11,7 @@ pub mod canvas {
 		/// The 1st dimension is the horizontal position, with 0 representing to the leftmost column
 		///
 		/// The 2nd dimension is the vertical position, with 0 representing the bottommost row
-		/// ```
+		/// ```ignore
 		/// // write "hi" on the bottom row
 		/// let canvas = Canvas::new();
 		/// canvas.map[0][0] = 'h';
@@ -28,6 +28,12 @@ pub mod canvas {
 		Black,
 		Gray,
 		DarkGray,
+		LightGray,
+		Red,
+		Yellow,
+		Green,
+		Blue,
+		Rgb(u8, u8, u8),
 	}
 
 	impl Color {
@@ -37,6 +43,72 @@ pub mod canvas {
 				Color::Black => String::from("black"),
 				Color::Gray => String::from("#333"),
 				Color::DarkGray => String::from("#111"),
+				Color::LightGray => String::from("#999"),
+				Color::Red => String::from("#e33"),
+				Color::Yellow => String::from("#ee3"),
+				Color::Green => String::from("#3c3"),
+				Color::Blue => String::from("#136"),
+				Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+			}
+		}
+
+		pub fn rgb(&self) -> (u8, u8, u8) {
+			match self {
+				Color::White => (255, 255, 255),
+				Color::Black => (0, 0, 0),
+				Color::Gray => (0x33, 0x33, 0x33),
+				Color::DarkGray => (0x11, 0x11, 0x11),
+				Color::LightGray => (0x99, 0x99, 0x99),
+				Color::Red => (0xee, 0x33, 0x33),
+				Color::Yellow => (0xee, 0xee, 0x33),
+				Color::Green => (0x33, 0xcc, 0x33),
+				Color::Blue => (0x11, 0x33, 0x66),
+				Color::Rgb(r, g, b) => (*r, *g, *b),
+			}
+		}
+
+		/// This color at a light level from 0.0 to 1.0, unchanged when fully lit
+		pub fn dimmed(self, level: f32) -> Color {
+			if level >= 1.0 || matches!(self, Color::Black) {
+				return self;
+			}
+			let (r, g, b) = self.rgb();
+			let scale = |channel: u8| (channel as f32 * level.max(0.0)) as u8;
+			Color::Rgb(scale(r), scale(g), scale(b))
+		}
+
+		/// Parse a color name as used in this enum (e.g. "darkgray"), or a "#rgb" or "#rrggbb" hex code
+		pub fn from_string(s: &str) -> Option<Color> {
+			match s.to_lowercase().as_str() {
+				"white" => Some(Color::White),
+				"black" => Some(Color::Black),
+				"gray" => Some(Color::Gray),
+				"darkgray" => Some(Color::DarkGray),
+				"lightgray" => Some(Color::LightGray),
+				"red" => Some(Color::Red),
+				"yellow" => Some(Color::Yellow),
+				"green" => Some(Color::Green),
+				"blue" => Some(Color::Blue),
+				hex => {
+					let digits = hex.strip_prefix('#')?;
+					if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+						return None;
+					}
+					let channel = |i: usize, len: usize| {
+						let value = u8::from_str_radix(&digits[i * len..(i + 1) * len], 16).ok()?;
+						Some(if len == 1 { value * 17 } else { value })
+					};
+					let len = match digits.len() {
+						3 => 1,
+						6 => 2,
+						_ => return None,
+					};
+					Some(Color::Rgb(
+						channel(0, len)?,
+						channel(1, len)?,
+						channel(2, len)?,
+					))
+				}
 			}
 		}
 	}
@@ -44,6 +116,7 @@ pub mod canvas {
 	#[derive(Copy, Clone)]
 	pub struct CanvasUnit {
 		pub character: char,
+		pub fg_color: Color,
 		pub bg_color: Color,
 		pub on_click: super::engine::Action,
 	}
@@ -52,6 +125,7 @@ pub mod canvas {
 		fn empty() -> CanvasUnit {
 			CanvasUnit {
 				character: ' ',
+				fg_color: Color::White,
 				bg_color: Color::Black,
 				on_click: super::engine::Action::None,
 			}
@@ -95,6 +169,19 @@ pub mod canvas {
 			c: char,
 			bg_color: Color,
 			action: super::engine::Action,
+		) {
+			self.set_colored(x, y, c, Color::White, bg_color, action);
+		}
+
+		/// `set`, with a foreground color for the character
+		pub fn set_colored<X: Into<i32>, Y: Into<i32>>(
+			&mut self,
+			x: X,
+			y: Y,
+			c: char,
+			fg_color: Color,
+			bg_color: Color,
+			action: super::engine::Action,
 		) {
 			let x_i32 = &x.into();
 			let y_i32 = &y.into();
@@ -109,6 +196,7 @@ pub mod canvas {
 				*y_i32,
 				CanvasUnit {
 					character: c,
+					fg_color,
 					bg_color,
 					on_click: action,
 				},
@@ -121,14 +209,22 @@ pub mod canvas {
 
 		/// Print the canvas to the console
 		pub fn print(&self) {
+			print!("{}", self.as_string());
+		}
+
+		/// The canvas' characters, one line per row from top to bottom
+		pub fn as_string(&self) -> String {
+			let mut canvas_string = String::new();
 			for y in (0..self.height).rev() {
 				let mut row_string: String = String::from("");
 				for x in 0..self.width {
-					let mut b = [0; 3];
+					let mut b = [0; 4];
 					row_string.push_str(self.get(x, y).character.encode_utf8(&mut b));
 				}
-				println!("{}", row_string);
+				canvas_string.push_str(&row_string);
+				canvas_string.push('\n');
 			}
+			canvas_string
 		}
 
 		/// Fill a selection with a character
@@ -163,7 +259,7 @@ pub mod canvas {
 		/// top left corner, top right corner, bottom right corner, bottom left corner, top/bottom wall, and left/right wall, in that order.
 		/// For the default frame, this would be "┌┐┘└─│"
 		///
-		/// ```
+		/// ```ignore
 		/// let canvas = Canvas::new();
 		/// canvas.draw_frame(
 		/// 	Coordinate::new(10,10),
@@ -257,7 +353,7 @@ pub mod canvas {
 		/// * `write_to` - The second Coordinate of the rectangular selection
 		/// * `text` - The string to write. \n are respected and \t are empty characters that don't overwrite the character behind them.
 		///
-		/// ```
+		/// ```ignore
 		/// let canvas = Canvas::new();
 		///	canvas.write_text(
 		///		Coordinate::new(10,10),
@@ -270,6 +366,17 @@ pub mod canvas {
 			write_from: super::engine::Coordinate,
 			write_to: super::engine::Coordinate,
 			text: &str,
+		) {
+			self.write_colored_text(write_from, write_to, text, Color::White);
+		}
+
+		/// `write_text` in the specified foreground color
+		pub fn write_colored_text(
+			&mut self,
+			write_from: super::engine::Coordinate,
+			write_to: super::engine::Coordinate,
+			text: &str,
+			fg_color: Color,
 		) {
 			let text_chars = text.chars();
 			let mut text_box_characters = Vec::new();
@@ -331,10 +438,11 @@ pub mod canvas {
 						text_box_characters[char_index].special_character,
 						SpecialCharacter::Empty
 					) {
-						self.set(
+						self.set_colored(
 							container_coords[0].x + x,
 							container_coords[1].y - y,
 							text_box_characters[char_index].character,
+							fg_color,
 							Color::Black,
 							super::engine::Action::None,
 						);
@@ -384,3 +492,405 @@ pub mod canvas {
 		[coord_one, coord_two]
 	}
 }
+
+pub mod ui {
+	use super::canvas::{sort_coordinates, Canvas, Color};
+	use super::engine::{Action, Coordinate};
+
+	/// Divides the canvas into a map viewport, a sidebar on the right and a message area along the bottom
+	pub struct Layout {
+		pub sidebar_width: i32,
+		pub message_height: i32,
+	}
+
+	impl Layout {
+		pub fn new<W: Into<i32>, H: Into<i32>>(sidebar_width: W, message_height: H) -> Layout {
+			Layout {
+				sidebar_width: sidebar_width.into(),
+				message_height: message_height.into(),
+			}
+		}
+
+		/// The selection passed to `draw_area`, leaving a 1-cell border around the map
+		pub fn viewport(&self, canvas: &Canvas) -> [Coordinate; 2] {
+			[
+				Coordinate::new(1, self.message_height + 1),
+				Coordinate::new(canvas.width - self.sidebar_width - 1, canvas.height - 1),
+			]
+		}
+
+		pub fn sidebar(&self, canvas: &Canvas) -> [Coordinate; 2] {
+			[
+				Coordinate::new(canvas.width - self.sidebar_width, 0),
+				Coordinate::new(canvas.width - 1, canvas.height - 1),
+			]
+		}
+
+		pub fn message_area(&self, canvas: &Canvas) -> [Coordinate; 2] {
+			[
+				Coordinate::new(0, 0),
+				Coordinate::new(
+					canvas.width - self.sidebar_width - 1,
+					self.message_height - 1,
+				),
+			]
+		}
+	}
+
+	pub enum Widget {
+		/// Word wrapped text occupying a fixed number of rows
+		Text { text: String, height: i32 },
+		/// One row per item, each in its own color
+		List(Vec<(String, Color)>),
+		/// A labeled bar filled in proportion to `value / max`
+		ProgressBar {
+			label: String,
+			value: i32,
+			max: i32,
+			color: Color,
+		},
+	}
+
+	impl Widget {
+		fn height(&self) -> i32 {
+			match self {
+				Widget::Text { height, .. } => *height,
+				Widget::List(items) => items.len() as i32,
+				Widget::ProgressBar { .. } => 1,
+			}
+		}
+
+		/// Draw the widget with its top left corner at `top_left`, returning the number of rows used
+		fn draw(&self, canvas: &mut Canvas, top_left: Coordinate, width: i32, rows: i32) -> i32 {
+			let height = self.height().min(rows);
+			if height <= 0 {
+				return 0;
+			}
+			match self {
+				Widget::Text { text, .. } => {
+					write_line(
+						canvas,
+						top_left,
+						Coordinate::new(top_left.x + width - 1, top_left.y - height + 1),
+						text,
+						Color::White,
+					);
+				}
+				Widget::List(items) => {
+					for (i, (item, color)) in items.iter().take(height as usize).enumerate() {
+						let y = top_left.y - i as i32;
+						write_line(
+							canvas,
+							Coordinate::new(top_left.x, y),
+							Coordinate::new(top_left.x + width - 1, y),
+							item,
+							*color,
+						);
+					}
+				}
+				Widget::ProgressBar {
+					label,
+					value,
+					max,
+					color,
+				} => {
+					let label_width = label.chars().count() as i32;
+					write_line(
+						canvas,
+						top_left,
+						Coordinate::new(top_left.x + width - 1, top_left.y),
+						label,
+						Color::White,
+					);
+					let bar_start = top_left.x + label_width + 1;
+					let bar_width = width - label_width - 1;
+					if bar_width > 0 {
+						let filled = if *max <= 0 {
+							0
+						} else {
+							bar_width * (*value).clamp(0, *max) / max
+						};
+						for x in 0..bar_width {
+							canvas.set(
+								bar_start + x,
+								top_left.y,
+								' ',
+								if x < filled { *color } else { Color::DarkGray },
+								Action::None,
+							);
+						}
+					}
+				}
+			}
+			height
+		}
+	}
+
+	/// A framed, titled box of widgets stacked from the top down
+	pub struct Panel {
+		pub title: String,
+		pub widgets: Vec<Widget>,
+	}
+
+	impl Panel {
+		pub fn new(title: &str) -> Panel {
+			Panel {
+				title: String::from(title),
+				widgets: vec![],
+			}
+		}
+
+		pub fn with(mut self, widget: Widget) -> Panel {
+			self.widgets.push(widget);
+			self
+		}
+
+		/// Clear the selection and draw the panel's frame, title and widgets inside it
+		pub fn draw(&self, canvas: &mut Canvas, draw_from: Coordinate, draw_to: Coordinate) {
+			let coords = sort_coordinates(draw_from, draw_to);
+			if coords[1].x - coords[0].x < 2 || coords[1].y - coords[0].y < 2 {
+				return;
+			}
+			canvas.fill(coords[0], coords[1], ' ', Color::Black);
+			canvas.draw_frame(coords[0], coords[1], "");
+			write_line(
+				canvas,
+				Coordinate::new(coords[0].x + 2, coords[1].y),
+				Coordinate::new(coords[1].x - 2, coords[1].y),
+				&self.title,
+				Color::White,
+			);
+			let width = coords[1].x - coords[0].x - 1;
+			let mut y = coords[1].y - 1;
+			for widget in &self.widgets {
+				let rows = y - coords[0].y;
+				if rows <= 0 {
+					break;
+				}
+				y -= widget.draw(canvas, Coordinate::new(coords[0].x + 1, y), width, rows);
+			}
+		}
+	}
+
+	/// `Canvas::write_colored_text`, skipping empty strings
+	fn write_line(
+		canvas: &mut Canvas,
+		write_from: Coordinate,
+		write_to: Coordinate,
+		text: &str,
+		color: Color,
+	) {
+		if !text.is_empty() {
+			canvas.write_colored_text(write_from, write_to, text, color);
+		}
+	}
+}
+
+pub mod camera {
+	use super::engine::Coordinate;
+	use json::{object, JsonValue};
+
+	#[derive(Copy, Clone, PartialEq)]
+	pub enum CameraMode {
+		/// Keep the target in the middle of the view
+		Centered,
+		/// Only scroll when the target leaves a box of this size around the middle of the view
+		DeadZone { width: i32, height: i32 },
+		/// Frame the whole room the target is in, centering on the target outside of rooms
+		/// and in rooms too big for the view
+		Room,
+	}
+
+	impl CameraMode {
+		/// The mode after this one, for cycling through them
+		pub fn next(&self) -> CameraMode {
+			match self {
+				CameraMode::Centered => CameraMode::DeadZone {
+					width: 16,
+					height: 8,
+				},
+				CameraMode::DeadZone { .. } => CameraMode::Room,
+				CameraMode::Room => CameraMode::Centered,
+			}
+		}
+
+		pub fn name(&self) -> &'static str {
+			match self {
+				CameraMode::Centered => "centered",
+				CameraMode::DeadZone { .. } => "dead zone",
+				CameraMode::Room => "room",
+			}
+		}
+
+		pub fn to_json(&self) -> JsonValue {
+			match self {
+				CameraMode::Centered => object!("name" => "centered"),
+				CameraMode::DeadZone { width, height } => object!(
+					"name" => "dead_zone",
+					"width" => *width,
+					"height" => *height,
+				),
+				CameraMode::Room => object!("name" => "room"),
+			}
+		}
+
+		pub fn from_json(value: &JsonValue) -> Option<CameraMode> {
+			match value["name"].as_str()? {
+				"centered" => Some(CameraMode::Centered),
+				"dead_zone" => Some(CameraMode::DeadZone {
+					width: value["width"].as_i32()?,
+					height: value["height"].as_i32()?,
+				}),
+				"room" => Some(CameraMode::Room),
+				_ => None,
+			}
+		}
+	}
+
+	/// Decides which world coordinate is shown in the middle of the map view
+	pub struct Camera {
+		pub mode: CameraMode,
+		pub center: Coordinate,
+	}
+
+	impl Camera {
+		pub fn new(mode: CameraMode, center: Coordinate) -> Camera {
+			Camera { mode, center }
+		}
+
+		pub fn to_json(&self) -> JsonValue {
+			object!(
+				"mode" => self.mode.to_json(),
+				"center" => self.center.to_json(),
+			)
+		}
+
+		pub fn from_json(value: &JsonValue) -> Option<Camera> {
+			Some(Camera {
+				mode: CameraMode::from_json(&value["mode"])?,
+				center: Coordinate::from_json(&value["center"])?,
+			})
+		}
+
+		/// Move the camera to keep `target` in a view of the given size.
+		/// `rooms` are the bottom left and top right corners of each room, walls included.
+		pub fn follow(
+			&mut self,
+			target: Coordinate,
+			rooms: &[[Coordinate; 2]],
+			view_width: i32,
+			view_height: i32,
+		) {
+			match self.mode {
+				CameraMode::Centered => self.center = target,
+				CameraMode::DeadZone { width, height } => {
+					let half_width = width.min(view_width - 2).max(0) / 2;
+					let half_height = height.min(view_height - 2).max(0) / 2;
+					self.center.x = self
+						.center
+						.x
+						.clamp(target.x - half_width, target.x + half_width);
+					self.center.y = self
+						.center
+						.y
+						.clamp(target.y - half_height, target.y + half_height);
+				}
+				CameraMode::Room => {
+					self.center = match rooms.iter().find(|room| {
+						(room[0].x..=room[1].x).contains(&target.x)
+							&& (room[0].y..=room[1].y).contains(&target.y)
+							&& room[1].x - room[0].x + 2 <= view_width
+							&& room[1].y - room[0].y + 2 <= view_height
+					}) {
+						Some(room) => Coordinate::new(
+							(room[0].x + room[1].x) / 2,
+							(room[0].y + room[1].y) / 2,
+						),
+						None => target,
+					}
+				}
+			}
+		}
+	}
+}
+
+pub mod viewport {
+	use super::canvas::sort_coordinates;
+	use super::engine::Coordinate;
+
+	/// Maps between canvas cells in the map view and the world coordinates drawn in them
+	#[derive(Copy, Clone)]
+	pub struct Viewport {
+		/// The bottom left cell of the view
+		pub from: Coordinate,
+		/// The cell beyond the top right of the view, which isn't drawn
+		pub to: Coordinate,
+		/// The world coordinate shown in the middle of the view
+		pub center: Coordinate,
+	}
+
+	impl Viewport {
+		/// A view of the cells in the selection, excluding its top row and right column
+		pub fn new(
+			screen_coord_1: Coordinate,
+			screen_coord_2: Coordinate,
+			center: Coordinate,
+		) -> Viewport {
+			let coords = sort_coordinates(screen_coord_1, screen_coord_2);
+			Viewport {
+				from: coords[0],
+				to: coords[1],
+				center,
+			}
+		}
+
+		/// The number of columns drawn
+		pub fn width(&self) -> i32 {
+			self.to.x - self.from.x
+		}
+
+		/// The number of rows drawn
+		pub fn height(&self) -> i32 {
+			self.to.y - self.from.y
+		}
+
+		/// The canvas cell the center is drawn in
+		fn screen_center(&self) -> Coordinate {
+			Coordinate::new(
+				self.from.x + (self.width() + 1) / 2,
+				self.from.y + (self.height() + 1) / 2,
+			)
+		}
+
+		pub fn contains_screen(&self, screen: Coordinate) -> bool {
+			(self.from.x..self.to.x).contains(&screen.x)
+				&& (self.from.y..self.to.y).contains(&screen.y)
+		}
+
+		/// The world coordinate drawn in a canvas cell, or None outside the view
+		pub fn to_world(&self, screen: Coordinate) -> Option<Coordinate> {
+			if !self.contains_screen(screen) {
+				return None;
+			}
+			let screen_center = self.screen_center();
+			Some(Coordinate::new(
+				self.center.x + screen.x - screen_center.x,
+				self.center.y + screen.y - screen_center.y,
+			))
+		}
+
+		/// The canvas cell a world coordinate is drawn in, or None if it's out of view
+		pub fn to_screen(&self, world: Coordinate) -> Option<Coordinate> {
+			let screen_center = self.screen_center();
+			let screen = Coordinate::new(
+				screen_center.x + world.x - self.center.x,
+				screen_center.y + world.y - self.center.y,
+			);
+			if self.contains_screen(screen) {
+				Some(screen)
+			} else {
+				None
+			}
+		}
+	}
+}
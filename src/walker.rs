@@ -0,0 +1,116 @@
+pub mod walker {
+	use crate::dungeon::dungeon::Dungeon;
+	use crate::engine::engine::Coordinate;
+	use crate::world::world::area::{Area, Tile};
+	use rand::Rng;
+	use std::collections::{HashSet, VecDeque};
+
+	const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+	pub struct WalkerConfig {
+		pub width: i32,
+		pub height: i32,
+		/// How many walkers carve at once, all starting from the spawn point
+		pub walkers: u32,
+		/// The fraction of the space inside the outer walls to carve into floor
+		pub coverage: f64,
+		/// The probability of a walker stepping onto uncarved ground when there's some next to it,
+		/// rather than picking any direction
+		pub exploration_bias: f64,
+	}
+
+	impl Default for WalkerConfig {
+		fn default() -> WalkerConfig {
+			WalkerConfig {
+				width: 80,
+				height: 40,
+				walkers: 4,
+				coverage: 0.35,
+				exploration_bias: 0.3,
+			}
+		}
+	}
+
+	/// Generate a level of meandering tunnels by carving floor wherever random walkers go.
+	/// Every walker starts from the spawn point so all the floor is connected,
+	/// and the stairs are put on the floor furthest from it.
+	pub fn generate<R: Rng>(config: WalkerConfig, rng: &mut R) -> Dungeon {
+		// the outermost tiles are kept for walls
+		let (min, max) = (
+			Coordinate::new(1, 1),
+			Coordinate::new(config.width - 2, config.height - 2),
+		);
+		let inside = ((max.x - min.x + 1) * (max.y - min.y + 1)).max(1);
+		let target = ((inside as f64 * config.coverage).ceil() as usize).clamp(1, inside as usize);
+		let spawn_point = Coordinate::new(config.width / 2, config.height / 2);
+
+		let mut carved = HashSet::from([spawn_point.as_tuple()]);
+		let mut walkers = vec![spawn_point; config.walkers.max(1) as usize];
+		// a walker boxed in by carved floor can wander for a long time, so give up eventually
+		let mut steps_left = inside as usize * 100;
+		while carved.len() < target && steps_left > 0 {
+			for walker in walkers.iter_mut() {
+				let neighbours: Vec<Coordinate> = DIRECTIONS
+					.iter()
+					.map(|(dx, dy)| Coordinate::new(walker.x + dx, walker.y + dy))
+					.filter(|next| {
+						next.x >= min.x && next.x <= max.x && next.y >= min.y && next.y <= max.y
+					})
+					.collect();
+				let unexplored: Vec<Coordinate> = neighbours
+					.iter()
+					.filter(|next| !carved.contains(&next.as_tuple()))
+					.copied()
+					.collect();
+				let choices = if !unexplored.is_empty() && rng.gen_bool(config.exploration_bias) {
+					unexplored
+				} else {
+					neighbours
+				};
+				if choices.is_empty() {
+					continue;
+				}
+				*walker = choices[rng.gen_range(0..choices.len())];
+				carved.insert(walker.as_tuple());
+			}
+			steps_left -= 1;
+		}
+
+		let mut area = Area::new(None);
+		for (x, y) in &carved {
+			area.set_tile(*x, *y, Tile::new(None));
+		}
+		// wall in the floor, like the walls around a region
+		for (x, y) in &carved {
+			for dx in -1..=1 {
+				for dy in -1..=1 {
+					if !carved.contains(&(x + dx, y + dy)) {
+						area.set_tile(x + dx, y + dy, Tile::wall());
+					}
+				}
+			}
+		}
+		let exit_point = furthest_floor(&carved, spawn_point);
+		if exit_point != spawn_point {
+			area.set_tile(exit_point.x, exit_point.y, Tile::stairs());
+		}
+		Dungeon { area, spawn_point }
+	}
+
+	/// The carved tile with the longest walk from `start`
+	fn furthest_floor(carved: &HashSet<(i32, i32)>, start: Coordinate) -> Coordinate {
+		let mut seen = HashSet::from([start.as_tuple()]);
+		let mut queue = VecDeque::from([start]);
+		let mut furthest = start;
+		while let Some(current) = queue.pop_front() {
+			furthest = current;
+			for (dx, dy) in DIRECTIONS {
+				let next = (current.x + dx, current.y + dy);
+				if carved.contains(&next) && seen.insert(next) {
+					queue.push_back(Coordinate::new(next.0, next.1));
+				}
+			}
+		}
+		furthest
+	}
+}
@@ -0,0 +1,521 @@
+pub mod wfc {
+	use crate::dungeon::dungeon::Dungeon;
+	use crate::engine::engine::Coordinate;
+	use crate::world::world::area::{Area, Tile};
+	use rand::Rng;
+	use std::collections::{HashMap, HashSet, VecDeque};
+
+	/// A small hand-drawn map of rooms, corridors and doors to learn from
+	pub const ROOMS_SAMPLE: &str = include_str!("../data/samples/rooms.txt");
+
+	/// The definition placed in the doorways of a sample
+	const DOOR_DEFINITION: &str = "door";
+
+	const WALL: u8 = 0;
+	const FLOOR: u8 = 1;
+	const DOOR: u8 = 2;
+
+	/// Right, down, left and up in the sample, where rows go down the page
+	const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+	pub struct WfcConfig {
+		pub width: i32,
+		pub height: i32,
+		/// The width and height of the patterns learnt from the sample
+		pub pattern_size: usize,
+		/// Also learn the rotated and mirrored versions of every pattern
+		pub symmetry: bool,
+		/// How many contradictions are undone before an attempt is abandoned
+		pub max_backtracks: u32,
+		/// How many more attempts are made after one is abandoned
+		pub retries: u32,
+	}
+
+	impl Default for WfcConfig {
+		fn default() -> WfcConfig {
+			WfcConfig {
+				width: 60,
+				height: 30,
+				pattern_size: 3,
+				symmetry: true,
+				max_backtracks: 200,
+				retries: 10,
+			}
+		}
+	}
+
+	/// Generate a level that looks like `sample`, an ASCII map of `#` walls, `.` floor and `+` doors,
+	/// using the overlapping model of wave function collapse.
+	/// The level is walled in and any floor that can't be reached from the spawn point is walled up.
+	pub fn generate<R: Rng>(
+		sample: &str,
+		config: WfcConfig,
+		rng: &mut R,
+	) -> Result<Dungeon, String> {
+		let n = config.pattern_size;
+		if n == 0 {
+			return Err(String::from("The pattern size must be at least 1"));
+		}
+		let (inner_width, inner_height) = (config.width - 2, config.height - 2);
+		if inner_width < n as i32 || inner_height < n as i32 {
+			return Err(format!(
+				"A {}x{} level is too small for {}x{} patterns",
+				config.width, config.height, n, n
+			));
+		}
+		let patterns = Patterns::learn(&parse_sample(sample)?, n, config.symmetry)?;
+		let mut wave = Wave::new(
+			&patterns,
+			(inner_width - n as i32 + 1) as usize,
+			(inner_height - n as i32 + 1) as usize,
+		);
+		for _ in 0..=config.retries {
+			if !wave.run(&patterns, config.max_backtracks, rng) {
+				wave.reset(&patterns);
+				continue;
+			}
+			let cells = wave.cells(&patterns, inner_width as usize, inner_height as usize);
+			if let Some(dungeon) = build(&cells, config.width, config.height, rng) {
+				return Ok(dungeon);
+			}
+			wave.reset(&patterns);
+		}
+		Err(format!(
+			"No level could be generated in {} attempts",
+			config.retries + 1
+		))
+	}
+
+	/// The sample's cells by row, from the top
+	fn parse_sample(sample: &str) -> Result<Vec<Vec<u8>>, String> {
+		let mut rows = vec![];
+		for line in sample.lines().filter(|line| !line.trim().is_empty()) {
+			let mut row = vec![];
+			for c in line.trim_end().chars() {
+				row.push(match c {
+					'#' => WALL,
+					'.' => FLOOR,
+					'+' => DOOR,
+					_ => return Err(format!("Unknown character '{}' in the sample", c)),
+				});
+			}
+			rows.push(row);
+		}
+		if rows.is_empty() {
+			return Err(String::from("The sample is empty"));
+		}
+		if rows.iter().any(|row| row.len() != rows[0].len()) {
+			return Err(String::from(
+				"The sample's rows must all be the same length",
+			));
+		}
+		Ok(rows)
+	}
+
+	/// The distinct NxN patterns in a sample, how often each appears,
+	/// and which can be placed next to each other
+	struct Patterns {
+		size: usize,
+		/// Each pattern's cells, row by row
+		cells: Vec<Vec<u8>>,
+		weights: Vec<f64>,
+		/// For each direction and pattern, the patterns that agree with it when overlapped one step that way
+		propagator: [Vec<Vec<usize>>; 4],
+	}
+
+	impl Patterns {
+		fn learn(sample: &[Vec<u8>], n: usize, symmetry: bool) -> Result<Patterns, String> {
+			let (width, height) = (sample[0].len(), sample.len());
+			if width < n || height < n {
+				return Err(format!(
+					"The sample must be at least {}x{} to learn {}x{} patterns",
+					n, n, n, n
+				));
+			}
+			let mut cells: Vec<Vec<u8>> = vec![];
+			let mut weights: Vec<f64> = vec![];
+			let mut index: HashMap<Vec<u8>, usize> = HashMap::new();
+			for y in 0..=height - n {
+				for x in 0..=width - n {
+					let mut pattern = vec![0; n * n];
+					for dy in 0..n {
+						for dx in 0..n {
+							pattern[dx + dy * n] = sample[y + dy][x + dx];
+						}
+					}
+					let variants = if symmetry {
+						symmetries(&pattern, n)
+					} else {
+						vec![pattern]
+					};
+					for variant in variants {
+						match index.get(&variant) {
+							Some(i) => weights[*i] += 1.0,
+							None => {
+								index.insert(variant.clone(), cells.len());
+								cells.push(variant);
+								weights.push(1.0);
+							}
+						}
+					}
+				}
+			}
+			let propagator = DIRECTIONS.map(|(dx, dy)| {
+				(0..cells.len())
+					.map(|p| {
+						(0..cells.len())
+							.filter(|q| agrees(&cells[p], &cells[*q], n, dx, dy))
+							.collect()
+					})
+					.collect()
+			});
+			Ok(Patterns {
+				size: n,
+				cells,
+				weights,
+				propagator,
+			})
+		}
+
+		fn len(&self) -> usize {
+			self.cells.len()
+		}
+	}
+
+	/// The pattern rotated four ways, and each of those mirrored
+	fn symmetries(pattern: &[u8], n: usize) -> Vec<Vec<u8>> {
+		let rotate = |p: &[u8]| -> Vec<u8> {
+			(0..n * n)
+				.map(|i| p[(n - 1 - i / n) + (i % n) * n])
+				.collect()
+		};
+		let mirror = |p: &[u8]| -> Vec<u8> {
+			(0..n * n)
+				.map(|i| p[(n - 1 - i % n) + (i / n) * n])
+				.collect()
+		};
+		let mut variants = vec![pattern.to_vec()];
+		for i in 0..3 {
+			let rotated = rotate(&variants[i]);
+			variants.push(rotated);
+		}
+		for i in 0..4 {
+			let mirrored = mirror(&variants[i]);
+			variants.push(mirrored);
+		}
+		variants
+	}
+
+	/// Whether `q` placed `(dx, dy)` from `p` has the same cells where the two overlap
+	fn agrees(p: &[u8], q: &[u8], n: usize, dx: i32, dy: i32) -> bool {
+		let n = n as i32;
+		for y in dy.max(0)..n.min(n + dy) {
+			for x in dx.max(0)..n.min(n + dx) {
+				if p[(x + y * n) as usize] != q[(x - dx + (y - dy) * n) as usize] {
+					return false;
+				}
+			}
+		}
+		true
+	}
+
+	/// Which patterns are still possible at each position, with what's needed to undo bans
+	struct Wave {
+		width: usize,
+		height: usize,
+		possible: Vec<bool>,
+		/// For each position, pattern and direction, how many patterns still possible at the
+		/// neighbour in the opposite direction allow the pattern here
+		supports: Vec<i32>,
+		remaining: Vec<usize>,
+		weight_sums: Vec<f64>,
+		weight_log_sums: Vec<f64>,
+		/// Every ban in order, so they can be undone
+		trail: Vec<(usize, usize)>,
+		contradiction: bool,
+	}
+
+	impl Wave {
+		fn new(patterns: &Patterns, width: usize, height: usize) -> Wave {
+			let mut wave = Wave {
+				width,
+				height,
+				possible: vec![],
+				supports: vec![],
+				remaining: vec![],
+				weight_sums: vec![],
+				weight_log_sums: vec![],
+				trail: vec![],
+				contradiction: false,
+			};
+			wave.reset(patterns);
+			wave
+		}
+
+		/// Make every pattern possible everywhere again
+		fn reset(&mut self, patterns: &Patterns) {
+			let positions = self.width * self.height;
+			let count = patterns.len();
+			let weight_sum: f64 = patterns.weights.iter().sum();
+			let weight_log_sum: f64 = patterns.weights.iter().map(|w| w * w.ln()).sum();
+			self.possible = vec![true; positions * count];
+			self.supports = Vec::with_capacity(positions * count * 4);
+			for _ in 0..positions {
+				for p in 0..count {
+					for d in 0..4 {
+						self.supports
+							.push(patterns.propagator[(d + 2) % 4][p].len() as i32);
+					}
+				}
+			}
+			self.remaining = vec![count; positions];
+			self.weight_sums = vec![weight_sum; positions];
+			self.weight_log_sums = vec![weight_log_sum; positions];
+			self.trail.clear();
+			self.contradiction = false;
+		}
+
+		/// Collapse every position to a single pattern, undoing decisions that lead to contradictions.
+		/// Returns false if that took more than `max_backtracks`.
+		fn run<R: Rng>(&mut self, patterns: &Patterns, max_backtracks: u32, rng: &mut R) -> bool {
+			// each decision is the length of the trail before it was made, where and what was chosen
+			let mut decisions: Vec<(usize, usize, usize)> = vec![];
+			let mut backtracks = 0;
+			while let Some(position) = self.lowest_entropy(rng) {
+				let chosen = self.choose(patterns, position, rng);
+				decisions.push((self.trail.len(), position, chosen));
+				for p in 0..patterns.len() {
+					if self.contradiction {
+						break;
+					}
+					if p != chosen && self.possible[position * patterns.len() + p] {
+						self.ban(patterns, position, p);
+					}
+				}
+				while self.contradiction {
+					backtracks += 1;
+					let (checkpoint, position, chosen) = match decisions.pop() {
+						Some(decision) if backtracks <= max_backtracks => decision,
+						_ => return false,
+					};
+					self.undo(patterns, checkpoint);
+					self.ban(patterns, position, chosen);
+				}
+			}
+			true
+		}
+
+		/// The undecided position with the least entropy, with a little noise to break ties
+		fn lowest_entropy<R: Rng>(&self, rng: &mut R) -> Option<usize> {
+			let mut lowest: Option<(usize, f64)> = None;
+			for position in 0..self.remaining.len() {
+				if self.remaining[position] <= 1 {
+					continue;
+				}
+				let sum = self.weight_sums[position];
+				let entropy =
+					sum.ln() - self.weight_log_sums[position] / sum + rng.gen_range(0.0..1e-6);
+				if lowest.is_none_or(|(_, lowest)| entropy < lowest) {
+					lowest = Some((position, entropy));
+				}
+			}
+			lowest.map(|(position, _)| position)
+		}
+
+		/// One of the patterns still possible at a position, weighted by how often it's in the sample
+		fn choose<R: Rng>(&self, patterns: &Patterns, position: usize, rng: &mut R) -> usize {
+			let count = patterns.len();
+			let mut roll = rng.gen_range(0.0..self.weight_sums[position]);
+			let mut last = 0;
+			for p in 0..count {
+				if self.possible[position * count + p] {
+					if roll < patterns.weights[p] {
+						return p;
+					}
+					roll -= patterns.weights[p];
+					last = p;
+				}
+			}
+			last
+		}
+
+		/// Rule out a pattern at a position, and then whatever that leaves unsupported
+		fn ban(&mut self, patterns: &Patterns, position: usize, pattern: usize) {
+			let count = patterns.len();
+			let mut pending = vec![(position, pattern)];
+			while let Some((position, pattern)) = pending.pop() {
+				if !self.possible[position * count + pattern] {
+					continue;
+				}
+				self.possible[position * count + pattern] = false;
+				self.trail.push((position, pattern));
+				let weight = patterns.weights[pattern];
+				self.remaining[position] -= 1;
+				self.weight_sums[position] -= weight;
+				self.weight_log_sums[position] -= weight * weight.ln();
+				for (d, neighbour) in self.neighbours(position) {
+					for q in &patterns.propagator[d][pattern] {
+						let support = &mut self.supports[(neighbour * count + q) * 4 + d];
+						*support -= 1;
+						if *support == 0 && self.possible[neighbour * count + q] {
+							pending.push((neighbour, *q));
+						}
+					}
+				}
+				if self.remaining[position] == 0 {
+					self.contradiction = true;
+					return;
+				}
+			}
+		}
+
+		/// Undo every ban made after the trail was `checkpoint` long
+		fn undo(&mut self, patterns: &Patterns, checkpoint: usize) {
+			let count = patterns.len();
+			while self.trail.len() > checkpoint {
+				let (position, pattern) = self.trail.pop().unwrap();
+				self.possible[position * count + pattern] = true;
+				let weight = patterns.weights[pattern];
+				self.remaining[position] += 1;
+				self.weight_sums[position] += weight;
+				self.weight_log_sums[position] += weight * weight.ln();
+				for (d, neighbour) in self.neighbours(position) {
+					for q in &patterns.propagator[d][pattern] {
+						self.supports[(neighbour * count + q) * 4 + d] += 1;
+					}
+				}
+			}
+			self.contradiction = false;
+		}
+
+		/// The positions next to one, with the direction each is in
+		fn neighbours(&self, position: usize) -> Vec<(usize, usize)> {
+			let (x, y) = (
+				(position % self.width) as i32,
+				(position / self.width) as i32,
+			);
+			DIRECTIONS
+				.iter()
+				.enumerate()
+				.filter_map(|(d, (dx, dy))| {
+					let (nx, ny) = (x + dx, y + dy);
+					if nx < 0 || ny < 0 || nx >= self.width as i32 || ny >= self.height as i32 {
+						return None;
+					}
+					Some((d, nx as usize + ny as usize * self.width))
+				})
+				.collect()
+		}
+
+		/// The cells of a fully collapsed wave by row, from the top
+		fn cells(&self, patterns: &Patterns, width: usize, height: usize) -> Vec<Vec<u8>> {
+			let count = patterns.len();
+			let n = patterns.size;
+			(0..height)
+				.map(|y| {
+					(0..width)
+						.map(|x| {
+							// positions only go as far as a whole pattern fits, the last ones covering the edges
+							let (px, py) = (x.min(self.width - 1), y.min(self.height - 1));
+							let position = px + py * self.width;
+							let pattern = (0..count)
+								.find(|p| self.possible[position * count + p])
+								.unwrap();
+							patterns.cells[pattern][(x - px) + (y - py) * n]
+						})
+						.collect()
+				})
+				.collect()
+		}
+	}
+
+	/// Turn generated cells into a walled in level, keeping only the largest connected stretch of floor.
+	/// Returns None if there's too little floor for a spawn point and stairs.
+	fn build<R: Rng>(cells: &[Vec<u8>], width: i32, height: i32, rng: &mut R) -> Option<Dungeon> {
+		// cells are by row from the top, while the area's y goes up
+		let to_area = |x: usize, y: usize| Coordinate::new(x as i32 + 1, height - 2 - y as i32);
+		let mut floor: HashSet<(i32, i32)> = HashSet::new();
+		for (y, row) in cells.iter().enumerate() {
+			for (x, cell) in row.iter().enumerate() {
+				if *cell != WALL {
+					floor.insert(to_area(x, y).as_tuple());
+				}
+			}
+		}
+		let mut largest: Vec<(i32, i32)> = vec![];
+		let mut unvisited = floor.clone();
+		let mut starts: Vec<(i32, i32)> = floor.iter().copied().collect();
+		starts.sort_unstable();
+		for start in starts {
+			if !unvisited.contains(&start) {
+				continue;
+			}
+			let region = flood(&unvisited, start);
+			for tile in &region {
+				unvisited.remove(tile);
+			}
+			if region.len() > largest.len() {
+				largest = region;
+			}
+		}
+		if largest.len() < 2 {
+			return None;
+		}
+
+		let mut area = Area::new(None);
+		area.fill(
+			Coordinate::new(0, 0),
+			Coordinate::new(width - 1, height - 1),
+			Tile::wall(),
+		);
+		let reachable: HashSet<(i32, i32)> = largest.iter().copied().collect();
+		for (y, row) in cells.iter().enumerate() {
+			for (x, cell) in row.iter().enumerate() {
+				let position = to_area(x, y);
+				if !reachable.contains(&position.as_tuple()) {
+					continue;
+				}
+				area.set_tile(position.x, position.y, Tile::new(None));
+				if *cell == DOOR {
+					area.spawn(DOOR_DEFINITION, position);
+				}
+			}
+		}
+		// neither the spawn point nor the stairs go in a doorway
+		let open: Vec<(i32, i32)> = largest
+			.iter()
+			.filter(|(x, y)| area.entities_at(*x, *y).is_empty())
+			.copied()
+			.collect();
+		if open.len() < 2 {
+			return None;
+		}
+		let spawn = open[rng.gen_range(0..open.len())];
+		let exit = flood(&reachable, spawn)
+			.into_iter()
+			.rev()
+			.find(|(x, y)| area.entities_at(*x, *y).is_empty())?;
+		area.set_tile(exit.0, exit.1, Tile::stairs());
+		Some(Dungeon {
+			area,
+			spawn_point: Coordinate::new(spawn.0, spawn.1),
+		})
+	}
+
+	/// The tiles reachable from `start` through `tiles`, nearest first
+	fn flood(tiles: &HashSet<(i32, i32)>, start: (i32, i32)) -> Vec<(i32, i32)> {
+		let mut seen = HashSet::from([start]);
+		let mut queue = VecDeque::from([start]);
+		let mut order = vec![];
+		while let Some((x, y)) = queue.pop_front() {
+			order.push((x, y));
+			for (dx, dy) in DIRECTIONS {
+				let next = (x + dx, y + dy);
+				if tiles.contains(&next) && seen.insert(next) {
+					queue.push_back(next);
+				}
+			}
+		}
+		order
+	}
+}
@@ -49,6 +49,14 @@ pub mod world {
 				}
 			}
 
+			/// The middle of the region, rounded up and to the right
+			pub fn get_center(&self) -> Coordinate {
+				Coordinate::new(
+					self.position.x + self.width / 2,
+					self.position.y + self.height / 2,
+				)
+			}
+
 			pub fn overlaps_coordinate(&self, coord: Coordinate) -> bool {
 				coord.y <= self.get_top_y()
 					&& coord.y >= self.position.y
@@ -59,12 +67,21 @@ pub mod world {
 	}
 
 	pub mod area {
+		use crate::content::definitions::{definitions, ObjectDefinition};
 		use crate::engine::engine::Coordinate;
+		use crate::entity::store::{Entities, EntityId};
 		use crate::render::canvas::Color;
 		use crate::world::world::region::Region;
+		use bresenham::Bresenham;
+		use json::JsonValue;
 		use rand::Rng;
-		use std::collections::HashMap;
+		use std::cmp::Reverse;
+		use std::collections::{BinaryHeap, HashMap};
+
+		/// How much more it costs a routed hallway to dig through a wall than to cross open space
+		const WALL_COST: i32 = 20;
 
+		#[derive(Clone)]
 		pub enum Hallway {
 			STRAIGHT {
 				vertical: bool,
@@ -78,8 +95,29 @@ pub mod world {
 				horizontal_distance: i32,
 				vertical_distance: i32,
 			},
+			/// A path dug tile by tile, for rooms no straight or bent hallway can join
+			ROUTED { path: Vec<Coordinate> },
+			/// Straight hallways side by side, making one two or three tiles wide
+			WIDE { lanes: Vec<Hallway> },
+			/// A path straight across at an angle, stepping sideways as it goes
+			DIAGONAL { path: Vec<Coordinate> },
+			/// A path wandering at random between the rooms
+			WINDING { path: Vec<Coordinate> },
 		}
 
+		#[derive(Copy, Clone, PartialEq, Debug)]
+		pub enum HallwayStyle {
+			/// One tile wide, straight or with a single bend
+			Narrow,
+			/// Two or three tiles wide and straight
+			Wide,
+			Diagonal,
+			Winding,
+		}
+
+		/// One end of a hallway: the wall it goes through, the way out of the room from there, and the room
+		type HallwayEnd<'a> = (Coordinate, (i32, i32), &'a Region);
+
 		#[derive(Copy, Clone)]
 		pub enum BoxCorner {
 			BottomRight, // ┘
@@ -91,6 +129,28 @@ pub mod world {
 		pub struct Area {
 			pub map: HashMap<i32, HashMap<i32, Tile>>,
 			pub default_fill: Option<WorldObject>,
+			/// The monsters and items in the area, referenced from the tiles they stand on
+			pub entities: Entities,
+			/// The bottom left and top right corners of each room, walls included
+			pub rooms: Vec<[Coordinate; 2]>,
+		}
+
+		/// The bottom left and top right corners of the smallest box containing every entry
+		/// of a map keyed by x and then y
+		pub fn grid_bounds<T>(map: &HashMap<i32, HashMap<i32, T>>) -> Option<[Coordinate; 2]> {
+			let mut bounds: Option<[Coordinate; 2]> = None;
+			for (x, x_col) in map {
+				for y in x_col.keys() {
+					bounds = Some(match bounds {
+						None => [Coordinate::new(*x, *y), Coordinate::new(*x, *y)],
+						Some([min, max]) => [
+							Coordinate::new(min.x.min(*x), min.y.min(*y)),
+							Coordinate::new(max.x.max(*x), max.y.max(*y)),
+						],
+					});
+				}
+			}
+			bounds
 		}
 
 		impl Area {
@@ -98,9 +158,19 @@ pub mod world {
 				Area {
 					map: HashMap::new(),
 					default_fill,
+					entities: Entities::new(),
+					rooms: vec![],
 				}
 			}
 
+			/// Remember a region's bounds as a room, for hallways to be routed around
+			pub fn add_room(&mut self, region: &Region) {
+				self.rooms.push([
+					region.position,
+					Coordinate::new(region.get_edge_x(), region.get_top_y()),
+				]);
+			}
+
 			pub fn place_region(&mut self, region: &Region) {
 				self.fill(
 					region.position,
@@ -114,18 +184,275 @@ pub mod world {
 				);
 			}
 
-			pub fn create_hallway(&mut self, region_1: &Region, region_2: &Region) {
-				let valid_hallways = self.get_valid_hallways(region_1, region_2);
-				self.create_hallway_from_valid(&valid_hallways);
+			pub fn create_hallway<R: Rng>(
+				&mut self,
+				region_1: &Region,
+				region_2: &Region,
+				rng: &mut R,
+			) {
+				self.create_styled_hallway(region_1, region_2, HallwayStyle::Narrow, rng);
+			}
+
+			pub fn create_styled_hallway<R: Rng>(
+				&mut self,
+				region_1: &Region,
+				region_2: &Region,
+				style: HallwayStyle,
+				rng: &mut R,
+			) {
+				let mut valid_hallways =
+					self.get_valid_styled_hallways(region_1, region_2, style, rng);
+				if valid_hallways.is_empty() {
+					valid_hallways.push(self.route_hallway(region_1, region_2));
+				}
+				self.create_hallway_from_valid(&valid_hallways, rng);
+			}
+
+			/// The hallways of a style that could join two regions, made from the narrow ones.
+			/// The narrow ones are given instead if none of the style fit.
+			pub fn get_valid_styled_hallways<R: Rng>(
+				&mut self,
+				region_1: &Region,
+				region_2: &Region,
+				style: HallwayStyle,
+				rng: &mut R,
+			) -> Vec<Hallway> {
+				let narrow = self.get_valid_hallways(region_1, region_2);
+				let regions = by_height(region_1, region_2);
+				let styled = match style {
+					HallwayStyle::Narrow => return narrow,
+					HallwayStyle::Wide => wide_hallways(&narrow, rng.gen_range(2..=3)),
+					HallwayStyle::Diagonal => self.diagonal_hallways(&narrow, regions),
+					HallwayStyle::Winding => self.winding_hallways(&narrow, regions, rng),
+				};
+				if styled.is_empty() {
+					narrow
+				} else {
+					styled
+				}
+			}
+
+			/// Diagonal hallways between the ends of narrow ones, crossing from one side of where
+			/// they could go to the other
+			fn diagonal_hallways(&self, narrow: &[Hallway], regions: [&Region; 2]) -> Vec<Hallway> {
+				let ends: Vec<[HallwayEnd; 2]> = narrow
+					.iter()
+					.filter_map(|hallway| hallway_ends(hallway, regions))
+					.collect();
+				let mut hallways = vec![];
+				for (i, [start, _]) in ends.iter().enumerate() {
+					let [_, end] = ends[ends.len() - 1 - i];
+					if std::ptr::eq(start.2, end.2) {
+						continue;
+					}
+					let (from, to) = (leave(start), leave(&end));
+					let (exit, entrance) = (*from.last().unwrap(), *to.last().unwrap());
+					if (exit.x - entrance.x).abs() < 2 || (exit.y - entrance.y).abs() < 2 {
+						continue;
+					}
+					let mut middle = vec![];
+					let mut previous = exit;
+					for (x, y) in Bresenham::new(exit.as_tuple(), entrance.as_tuple()).skip(1) {
+						let next = Coordinate::new(x as i32, y as i32);
+						// step sideways first unless that's into one of the rooms
+						if next.x != previous.x && next.y != previous.y {
+							let corner = Coordinate::new(next.x, previous.y);
+							middle.push(if overlaps(regions, corner) {
+								Coordinate::new(previous.x, next.y)
+							} else {
+								corner
+							});
+						}
+						middle.push(next);
+						previous = next;
+					}
+					if previous.x != entrance.x && previous.y != entrance.y {
+						let corner = Coordinate::new(entrance.x, previous.y);
+						middle.push(if overlaps(regions, corner) {
+							Coordinate::new(previous.x, entrance.y)
+						} else {
+							corner
+						});
+					}
+					if middle
+						.iter()
+						.all(|position| self.is_open(regions, *position))
+					{
+						let path = from
+							.into_iter()
+							.chain(middle)
+							.chain(to.into_iter().rev())
+							.collect();
+						hallways.push(Hallway::DIAGONAL { path });
+					}
+				}
+				hallways
+			}
+
+			/// Random walks between the ends of a few of the narrow hallways, mostly heading
+			/// towards the other room
+			fn winding_hallways<R: Rng>(
+				&self,
+				narrow: &[Hallway],
+				regions: [&Region; 2],
+				rng: &mut R,
+			) -> Vec<Hallway> {
+				let mut hallways = vec![];
+				if narrow.is_empty() {
+					return hallways;
+				}
+				for _ in 0..4 {
+					let [start, end] =
+						match hallway_ends(&narrow[rng.gen_range(0..narrow.len())], regions) {
+							Some(ends) => ends,
+							None => continue,
+						};
+					let (from, to) = (leave(&start), leave(&end));
+					let (exit, entrance) = (*from.last().unwrap(), *to.last().unwrap());
+					let distance = |position: Coordinate| {
+						(position.x - entrance.x).abs() + (position.y - entrance.y).abs()
+					};
+					let margin = 3;
+					let (min, max) = (
+						Coordinate::new(
+							exit.x.min(entrance.x) - margin,
+							exit.y.min(entrance.y) - margin,
+						),
+						Coordinate::new(
+							exit.x.max(entrance.x) + margin,
+							exit.y.max(entrance.y) + margin,
+						),
+					);
+					let mut middle = vec![];
+					let mut current = exit;
+					for _ in 0..4 * distance(exit) + 40 {
+						if distance(current) <= 1 {
+							let path = from
+								.iter()
+								.copied()
+								.chain(middle)
+								.chain(to.iter().rev().copied())
+								.collect();
+							hallways.push(Hallway::WINDING { path });
+							break;
+						}
+						let options: Vec<Coordinate> = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+							.iter()
+							.map(|(dx, dy)| Coordinate::new(current.x + dx, current.y + dy))
+							.filter(|next| {
+								next.x >= min.x
+									&& next.x <= max.x && next.y >= min.y
+									&& next.y <= max.y && self.is_open(regions, *next)
+							})
+							.collect();
+						let closer: Vec<Coordinate> = options
+							.iter()
+							.copied()
+							.filter(|next| distance(*next) < distance(current))
+							.collect();
+						let choices = if !closer.is_empty() && rng.gen_bool(0.6) {
+							closer
+						} else {
+							options
+						};
+						if choices.is_empty() {
+							break;
+						}
+						current = choices[rng.gen_range(0..choices.len())];
+						middle.push(current);
+					}
+				}
+				hallways
+			}
+
+			/// Whether a hallway can be dug through a position outside the rooms it joins
+			fn is_open(&self, regions: [&Region; 2], position: Coordinate) -> bool {
+				!overlaps(regions, position)
+					&& self.get_tile_at(position.x, position.y).contents.is_empty()
+			}
+
+			/// Find the cheapest path from the middle of one region to the middle of the other
+			/// with A*, going around walls and the other rooms in `rooms` rather than through them where it can
+			pub fn route_hallway(&self, region_1: &Region, region_2: &Region) -> Hallway {
+				let margin = 4;
+				let min = Coordinate::new(
+					region_1.position.x.min(region_2.position.x) - margin,
+					region_1.position.y.min(region_2.position.y) - margin,
+				);
+				let max = Coordinate::new(
+					region_1.get_edge_x().max(region_2.get_edge_x()) + margin,
+					region_1.get_top_y().max(region_2.get_top_y()) + margin,
+				);
+				let (start, goal) = (region_1.get_center(), region_2.get_center());
+				let estimate = |(x, y): (i32, i32)| (x - goal.x).abs() + (y - goal.y).abs();
+				let mut costs = HashMap::from([(start.as_tuple::<i32>(), 0)]);
+				let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+				let mut open =
+					BinaryHeap::from([Reverse((estimate(start.as_tuple()), start.as_tuple()))]);
+				while let Some(Reverse((_, current))) = open.pop() {
+					if current == goal.as_tuple() {
+						break;
+					}
+					for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+						let next = (current.0 + dx, current.1 + dy);
+						if next.0 < min.x || next.0 > max.x || next.1 < min.y || next.1 > max.y {
+							continue;
+						}
+						let position = Coordinate::new(next.0, next.1);
+						// the two rooms being joined are dug into freely, to reach floor inside any shape
+						let step = if region_1.overlaps_coordinate(position)
+							|| region_2.overlaps_coordinate(position)
+						{
+							1
+						} else if self.in_room(position)
+							|| !self.get_tile_at(next.0, next.1).contents.is_empty()
+						{
+							WALL_COST
+						} else {
+							1
+						};
+						let cost = costs[&current] + step;
+						if costs.get(&next).is_none_or(|known| cost < *known) {
+							costs.insert(next, cost);
+							came_from.insert(next, current);
+							open.push(Reverse((cost + estimate(next), next)));
+						}
+					}
+				}
+				let mut path = vec![goal];
+				let mut current = goal.as_tuple();
+				while let Some(previous) = came_from.get(&current) {
+					path.push(Coordinate::new(previous.0, previous.1));
+					current = *previous;
+				}
+				path.reverse();
+				Hallway::ROUTED { path }
+			}
+
+			fn in_room(&self, position: Coordinate) -> bool {
+				self.rooms.iter().any(|[min, max]| {
+					position.x >= min.x
+						&& position.x <= max.x
+						&& position.y >= min.y
+						&& position.y <= max.y
+				})
 			}
 
-			pub fn create_hallway_from_valid(&mut self, valid_hallways: &Vec<Hallway>) {
-				if valid_hallways.len() == 0 {
+			pub fn create_hallway_from_valid<R: Rng>(
+				&mut self,
+				valid_hallways: &Vec<Hallway>,
+				rng: &mut R,
+			) {
+				if valid_hallways.is_empty() {
 					return;
 				}
 				let hallway = valid_hallways
-					.get(rand::thread_rng().gen_range(0..valid_hallways.len()))
+					.get(rng.gen_range(0..valid_hallways.len()))
 					.unwrap();
+				self.carve(hallway);
+			}
+
+			fn carve(&mut self, hallway: &Hallway) {
 				match *hallway {
 					Hallway::STRAIGHT {
 						vertical,
@@ -133,7 +460,7 @@ pub mod world {
 						start,
 						end,
 					} => {
-						self.fill(
+						self.enclose(
 							Coordinate::new(
 								if vertical { x_y - 1 } else { start },
 								if vertical { start } else { x_y - 1 },
@@ -142,7 +469,6 @@ pub mod world {
 								if vertical { x_y + 1 } else { end },
 								if vertical { end } else { x_y + 1 },
 							),
-							Tile::wall(),
 						);
 						self.fill(
 							Coordinate::new(
@@ -173,15 +499,13 @@ pub mod world {
 							}
 						};
 
-						self.fill(
+						self.enclose(
 							Coordinate::new(turning_point.x, turning_point.y - 1),
 							Coordinate::new(x, turning_point.y + 1),
-							Tile::wall(),
 						);
-						self.fill(
+						self.enclose(
 							Coordinate::new(turning_point.x - 1, turning_point.y),
 							Coordinate::new(turning_point.x + 1, y),
-							Tile::wall(),
 						);
 
 						let (corner_x, corner_y) = match orientation {
@@ -190,10 +514,9 @@ pub mod world {
 							BoxCorner::BottomLeft => (-1, -1),
 							BoxCorner::BottomRight => (1, -1),
 						};
-						self.set_tile(
-							turning_point.x + corner_x,
-							turning_point.y + corner_y,
-							Tile::wall(),
+						self.enclose(
+							Coordinate::new(turning_point.x + corner_x, turning_point.y + corner_y),
+							Coordinate::new(turning_point.x + corner_x, turning_point.y + corner_y),
 						);
 
 						self.fill(
@@ -207,6 +530,25 @@ pub mod world {
 							Tile::new(None),
 						);
 					}
+					Hallway::ROUTED { ref path }
+					| Hallway::DIAGONAL { ref path }
+					| Hallway::WINDING { ref path } => {
+						for position in path {
+							self.set_tile(position.x, position.y, Tile::new(None));
+						}
+						for position in path {
+							self.enclose(
+								Coordinate::new(position.x - 1, position.y - 1),
+								Coordinate::new(position.x + 1, position.y + 1),
+							);
+						}
+					}
+					// each lane digs through the wall the one before it put up
+					Hallway::WIDE { ref lanes } => {
+						for lane in lanes {
+							self.carve(lane);
+						}
+					}
 				};
 			}
 
@@ -215,11 +557,7 @@ pub mod world {
 				region_1: &Region,
 				region_2: &Region,
 			) -> Vec<Hallway> {
-				let regions = if region_2.position.y > region_1.position.y {
-					[region_1, region_2]
-				} else {
-					[region_2, region_1]
-				};
+				let regions = by_height(region_1, region_2);
 				let mut possible_hallways = vec![];
 				let (vertical, horizontal) = if regions[0].get_top_y() >= regions[1].position.y + 2
 					&& regions[1].get_top_y() >= regions[0].position.y + 2
@@ -375,21 +713,118 @@ pub mod world {
 								),
 							)
 						}
+						// hallways of the other kinds are made from valid ones
+						Hallway::ROUTED { .. }
+						| Hallway::WIDE { .. }
+						| Hallway::DIAGONAL { .. }
+						| Hallway::WINDING { .. } => true,
 					};
 					if is_valid {
-						valid_hallways.push(hallway);
+						if let Some(hallway) = self.attach_hallway(hallway, regions) {
+							valid_hallways.push(hallway);
+						}
 					}
 				}
 				valid_hallways
 			}
 
+			/// Lengthen a hallway that reaches the edges of two rooms so that it goes through
+			/// to their floor, for rooms that aren't rectangles
+			fn attach_hallway(&self, hallway: Hallway, regions: [&Region; 2]) -> Option<Hallway> {
+				match hallway {
+					Hallway::STRAIGHT {
+						vertical,
+						position,
+						start,
+						end,
+					} => {
+						let (low, high) = straight_regions(vertical, regions);
+						let step = if vertical { (0, 1) } else { (1, 0) };
+						let at = |xy: i32| {
+							if vertical {
+								Coordinate::new(position, xy)
+							} else {
+								Coordinate::new(xy, position)
+							}
+						};
+						let start = self.attachment_point(low, at(start), (-step.0, -step.1))?;
+						let end = self.attachment_point(high, at(end), step)?;
+						Some(Hallway::STRAIGHT {
+							vertical,
+							position,
+							start: if vertical { start.y } else { start.x },
+							end: if vertical { end.y } else { end.x },
+						})
+					}
+					Hallway::BENT {
+						orientation,
+						turning_point,
+						horizontal_distance,
+						vertical_distance,
+					} => {
+						let (horizontal_region, vertical_region) =
+							bent_regions(orientation, regions);
+						let (step_x, step_y) = bent_steps(orientation);
+						let horizontal_end = self.attachment_point(
+							horizontal_region,
+							Coordinate::new(
+								turning_point.x + step_x * horizontal_distance,
+								turning_point.y,
+							),
+							(step_x, 0),
+						)?;
+						let vertical_end = self.attachment_point(
+							vertical_region,
+							Coordinate::new(
+								turning_point.x,
+								turning_point.y + step_y * vertical_distance,
+							),
+							(0, step_y),
+						)?;
+						Some(Hallway::BENT {
+							orientation,
+							turning_point,
+							horizontal_distance: (horizontal_end.x - turning_point.x).abs(),
+							vertical_distance: (vertical_end.y - turning_point.y).abs(),
+						})
+					}
+					Hallway::ROUTED { .. }
+					| Hallway::WIDE { .. }
+					| Hallway::DIAGONAL { .. }
+					| Hallway::WINDING { .. } => Some(hallway),
+				}
+			}
+
+			/// The wall a hallway going into a room from `edge` should stop at, being the last tile
+			/// before the room's floor. None if there's no floor that way.
+			fn attachment_point(
+				&self,
+				region: &Region,
+				edge: Coordinate,
+				step: (i32, i32),
+			) -> Option<Coordinate> {
+				let mut point = edge;
+				loop {
+					let next = Coordinate::new(point.x + step.0, point.y + step.1);
+					if !region.overlaps_coordinate(next) {
+						return None;
+					}
+					if self.tile_exists(next.x, next.y)
+						&& self.get_tile_at(next.x, next.y).contents.is_empty()
+					{
+						return Some(point);
+					}
+					point = next;
+				}
+			}
+
 			pub fn get_tile_at<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> Tile {
 				let x_i32 = &x.into();
 				let y_i32 = &y.into();
 				if self.tile_exists(*x_i32, *y_i32) {
 					return self.map.get(x_i32).unwrap().get(y_i32).unwrap().clone();
 				}
-				Tile { contents: vec![] }
+				Tile::new(None)
 			}
 
 			pub fn tile_exists<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> bool {
@@ -427,6 +862,178 @@ pub mod world {
 				x_col.insert(y_i32, t);
 			}
 
+			/// Every set tile as `[x, y, terrain, object...]`
+			pub fn to_json(&self) -> JsonValue {
+				let mut tiles = vec![];
+				for (x, x_col) in &self.map {
+					for (y, tile) in x_col {
+						let mut entry = vec![
+							JsonValue::from(*x),
+							JsonValue::from(*y),
+							JsonValue::from(tile.terrain.as_string()),
+						];
+						for obj in &tile.contents {
+							entry.push(JsonValue::from(obj.as_string()));
+						}
+						tiles.push(JsonValue::Array(entry));
+					}
+				}
+				json::object!(
+					"default_fill" => match self.default_fill {
+						Some(obj) => JsonValue::from(obj.as_string()),
+						None => JsonValue::Null,
+					},
+					"tiles" => JsonValue::Array(tiles),
+					"entities" => self.entities.to_json(),
+					"rooms" => JsonValue::Array(
+						self.rooms
+							.iter()
+							.map(|room| json::array![room[0].x, room[0].y, room[1].x, room[1].y])
+							.collect()
+					),
+				)
+			}
+
+			pub fn from_json(value: &JsonValue) -> Option<Area> {
+				let default_fill = if value["default_fill"].is_null() {
+					None
+				} else {
+					Some(WorldObject::from_string(value["default_fill"].as_str()?)?)
+				};
+				let mut area = Area::new(default_fill);
+				for entry in value["tiles"].members() {
+					let terrain = Terrain::from_string(entry[2].as_str()?)?;
+					let mut contents = vec![];
+					for obj in entry.members().skip(3) {
+						contents.push(WorldObject::from_string(obj.as_str()?)?);
+					}
+					area.set_tile(
+						entry[0].as_i32()?,
+						entry[1].as_i32()?,
+						Tile { terrain, contents },
+					);
+				}
+				if !value["entities"].is_null() {
+					area.entities = Entities::from_json(&value["entities"])?;
+				}
+				for room in value["rooms"].members() {
+					area.rooms.push([
+						Coordinate::new(room[0].as_i32()?, room[1].as_i32()?),
+						Coordinate::new(room[2].as_i32()?, room[3].as_i32()?),
+					]);
+				}
+				Some(area)
+			}
+
+			/// Create an entity from its definition and place it, or None if there's no such definition
+			pub fn spawn(&mut self, definition_id: &str, position: Coordinate) -> Option<EntityId> {
+				let definition = definitions().get(definition_id)?.clone();
+				let id = self.entities.create_from_definition(&definition);
+				self.place_entity(id, position);
+				Some(id)
+			}
+
+			/// Put an entity on the tile at a position, taking it off the tile it was on
+			pub fn place_entity(&mut self, id: EntityId, position: Coordinate) {
+				self.take_entity(id);
+				let mut tile = self.get_tile_at(position.x, position.y);
+				tile.contents.push(WorldObject::ENTITY(id));
+				self.set_tile(position.x, position.y, tile);
+				self.entities.set_position(id, position);
+			}
+
+			/// Take an entity off the map without removing its components
+			pub fn take_entity(&mut self, id: EntityId) {
+				if let Some(old) = self.entities.positions.get(&id).copied() {
+					if let Some(tile) = self
+						.map
+						.get_mut(&old.x)
+						.and_then(|x_col| x_col.get_mut(&old.y))
+					{
+						tile.contents.retain(
+							|obj| !matches!(obj, WorldObject::ENTITY(other) if *other == id),
+						);
+					}
+					self.entities.clear_position(id);
+				}
+			}
+
+			/// Take an item off the map and put it into a holder's inventory
+			pub fn give(&mut self, holder: EntityId, item: EntityId) {
+				self.take_entity(item);
+				self.entities.give(holder, item);
+			}
+
+			/// Remove an entity from the map and the entity store
+			pub fn remove_entity(&mut self, id: EntityId) {
+				self.take_entity(id);
+				self.entities.remove(id);
+			}
+
+			pub fn entities_at<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> &[EntityId] {
+				self.entities.at(x, y)
+			}
+
+			/// Whether the tile or any entity on it blocks sight
+			pub fn blocks_sight_at<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> bool {
+				let (x, y) = (x.into(), y.into());
+				self.get_tile_at(x, y).blocks_sight()
+					|| self
+						.entities_at(x, y)
+						.iter()
+						.any(|id| self.entities.blocks_sight.contains(id))
+			}
+
+			/// Whether the tile can be walked on and no entity on it is in the way
+			pub fn is_walkable_at<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> bool {
+				let (x, y) = (x.into(), y.into());
+				self.get_tile_at(x, y).is_walkable()
+					&& !self
+						.entities_at(x, y)
+						.iter()
+						.any(|id| self.entities.blocks_movement.contains(id))
+			}
+
+			/// The first entity on a tile that blocks movement
+			pub fn blocker_at<X: Into<i32>, Y: Into<i32>>(&self, x: X, y: Y) -> Option<EntityId> {
+				self.entities_at(x, y)
+					.iter()
+					.copied()
+					.find(|id| self.entities.blocks_movement.contains(id))
+			}
+
+			/// The character, foreground and background color to draw for a tile,
+			/// showing the most recently arrived entity on it over the tile itself
+			pub fn appearance_at<X: Into<i32>, Y: Into<i32>>(
+				&self,
+				x: X,
+				y: Y,
+			) -> (char, Color, Color) {
+				let (x, y) = (x.into(), y.into());
+				let tile = self.get_tile_at(x, y);
+				let bg_color = tile.get_bgcolor().unwrap_or(tile.terrain.bg_color());
+				for id in self.entities_at(x, y).iter().rev() {
+					if let Some(renderable) = self.entities.renderables.get(id) {
+						let bg_color = match renderable.bg_color {
+							Color::Black => bg_color,
+							color => color,
+						};
+						return (renderable.glyph, renderable.fg_color, bg_color);
+					}
+				}
+				(tile.get_char(), tile.get_fgcolor(), bg_color)
+			}
+
+			/// The number of tiles that have been set
+			pub fn tile_count(&self) -> i32 {
+				self.map.values().map(|x_col| x_col.len() as i32).sum()
+			}
+
+			/// The bottom left and top right corners of the smallest box containing every set tile
+			pub fn bounds(&self) -> Option<[Coordinate; 2]> {
+				grid_bounds(&self.map)
+			}
+
 			pub fn region_is_empty(&self, coord_1: Coordinate, coord_2: Coordinate) -> bool {
 				let coords = crate::render::canvas::sort_coordinates(coord_1, coord_2);
 				for x in coords[0].x..=coords[1].x {
@@ -439,6 +1046,18 @@ pub mod world {
 				true
 			}
 
+			/// Put walls on the tiles between two corners that nothing has been placed on yet
+			pub fn enclose(&mut self, coord_1: Coordinate, coord_2: Coordinate) {
+				let coords = crate::render::canvas::sort_coordinates(coord_1, coord_2);
+				for x in coords[0].x..=coords[1].x {
+					for y in coords[0].y..=coords[1].y {
+						if !self.tile_exists(x, y) {
+							self.set_tile(x, y, Tile::wall());
+						}
+					}
+				}
+			}
+
 			pub fn fill(&mut self, coord_1: Coordinate, coord_2: Coordinate, tile: Tile) {
 				let coords = crate::render::canvas::sort_coordinates(coord_1, coord_2);
 				for x in coords[0].x..=coords[1].x {
@@ -449,14 +1068,166 @@ pub mod world {
 			}
 		}
 
+		/// Two regions, the lower one first
+		fn by_height<'a>(region_1: &'a Region, region_2: &'a Region) -> [&'a Region; 2] {
+			if region_2.position.y > region_1.position.y {
+				[region_1, region_2]
+			} else {
+				[region_2, region_1]
+			}
+		}
+
+		/// The regions at the start and end of a straight hallway between two regions sorted by height
+		fn straight_regions(vertical: bool, regions: [&Region; 2]) -> (&Region, &Region) {
+			if vertical || regions[0].position.x <= regions[1].position.x {
+				(regions[0], regions[1])
+			} else {
+				(regions[1], regions[0])
+			}
+		}
+
+		/// The regions at the ends of a bent hallway's horizontal and vertical parts
+		fn bent_regions(orientation: BoxCorner, regions: [&Region; 2]) -> (&Region, &Region) {
+			match orientation {
+				BoxCorner::BottomLeft | BoxCorner::BottomRight => (regions[0], regions[1]),
+				BoxCorner::TopLeft | BoxCorner::TopRight => (regions[1], regions[0]),
+			}
+		}
+
+		/// The directions a bent hallway goes from its turning point towards each region
+		fn bent_steps(orientation: BoxCorner) -> (i32, i32) {
+			(
+				match orientation {
+					BoxCorner::BottomLeft | BoxCorner::TopLeft => 1,
+					BoxCorner::BottomRight | BoxCorner::TopRight => -1,
+				},
+				match orientation {
+					BoxCorner::BottomLeft | BoxCorner::BottomRight => 1,
+					BoxCorner::TopLeft | BoxCorner::TopRight => -1,
+				},
+			)
+		}
+
+		fn overlaps(regions: [&Region; 2], position: Coordinate) -> bool {
+			regions
+				.iter()
+				.any(|region| region.overlaps_coordinate(position))
+		}
+
+		/// Where a narrow hallway goes into each of the regions it joins
+		fn hallway_ends<'a>(
+			hallway: &Hallway,
+			regions: [&'a Region; 2],
+		) -> Option<[HallwayEnd<'a>; 2]> {
+			match *hallway {
+				Hallway::STRAIGHT {
+					vertical,
+					position,
+					start,
+					end,
+				} => {
+					let (low, high) = straight_regions(vertical, regions);
+					let at = |xy: i32| {
+						if vertical {
+							Coordinate::new(position, xy)
+						} else {
+							Coordinate::new(xy, position)
+						}
+					};
+					let step = if vertical { (0, 1) } else { (1, 0) };
+					Some([(at(start), step, low), (at(end), (-step.0, -step.1), high)])
+				}
+				Hallway::BENT {
+					orientation,
+					turning_point,
+					horizontal_distance,
+					vertical_distance,
+				} => {
+					let (horizontal_region, vertical_region) = bent_regions(orientation, regions);
+					let (step_x, step_y) = bent_steps(orientation);
+					Some([
+						(
+							Coordinate::new(
+								turning_point.x + step_x * horizontal_distance,
+								turning_point.y,
+							),
+							(-step_x, 0),
+							horizontal_region,
+						),
+						(
+							Coordinate::new(
+								turning_point.x,
+								turning_point.y + step_y * vertical_distance,
+							),
+							(0, -step_y),
+							vertical_region,
+						),
+					])
+				}
+				_ => None,
+			}
+		}
+
+		/// The tiles from where a hallway goes into a room out to the edge of the room's region
+		fn leave(end: &HallwayEnd) -> Vec<Coordinate> {
+			let (position, (dx, dy), region) = *end;
+			let mut tiles = vec![position];
+			let mut next = Coordinate::new(position.x + dx, position.y + dy);
+			while region.overlaps_coordinate(next) {
+				tiles.push(next);
+				next = Coordinate::new(next.x + dx, next.y + dy);
+			}
+			tiles
+		}
+
+		/// Straight hallways next to each other merged into ones `width` wide, or narrower
+		/// if there aren't that many side by side anywhere
+		fn wide_hallways(narrow: &[Hallway], width: i32) -> Vec<Hallway> {
+			let lanes: HashMap<(bool, i32), &Hallway> = narrow
+				.iter()
+				.filter_map(|hallway| match *hallway {
+					Hallway::STRAIGHT {
+						vertical, position, ..
+					} => Some(((vertical, position), hallway)),
+					_ => None,
+				})
+				.collect();
+			for width in (2..=width).rev() {
+				let mut hallways = vec![];
+				for hallway in narrow {
+					if let Hallway::STRAIGHT {
+						vertical, position, ..
+					} = *hallway
+					{
+						let side_by_side: Option<Vec<Hallway>> = (0..width)
+							.map(|i| {
+								lanes
+									.get(&(vertical, position + i))
+									.map(|lane| (*lane).clone())
+							})
+							.collect();
+						if let Some(lanes) = side_by_side {
+							hallways.push(Hallway::WIDE { lanes });
+						}
+					}
+				}
+				if !hallways.is_empty() {
+					return hallways;
+				}
+			}
+			vec![]
+		}
+
 		#[derive(Clone)]
 		pub struct Tile {
+			pub terrain: Terrain,
 			pub contents: Vec<WorldObject>,
 		}
 
 		impl Tile {
 			pub fn new(tile: Option<WorldObject>) -> Tile {
 				Tile {
+					terrain: Terrain::Floor,
 					contents: match tile {
 						Some(obj) => vec![obj],
 						None => vec![],
@@ -468,6 +1239,41 @@ pub mod world {
 				Tile::new(Some(WorldObject::WALL))
 			}
 
+			pub fn terrain(terrain: Terrain) -> Tile {
+				Tile {
+					terrain,
+					contents: vec![],
+				}
+			}
+
+			pub fn stairs() -> Tile {
+				Tile::new(Some(WorldObject::STAIRS))
+			}
+
+			pub fn contains_stairs(&self) -> bool {
+				self.contents
+					.iter()
+					.any(|obj| matches!(obj, WorldObject::STAIRS))
+			}
+
+			pub fn up_stairs() -> Tile {
+				Tile::new(Some(WorldObject::UPSTAIRS))
+			}
+
+			pub fn contains_up_stairs(&self) -> bool {
+				self.contents
+					.iter()
+					.any(|obj| matches!(obj, WorldObject::UPSTAIRS))
+			}
+
+			pub fn blocks_sight(&self) -> bool {
+				!self.terrain.transparent() || self.contents.iter().any(|obj| obj.blocks_sight())
+			}
+
+			pub fn is_walkable(&self) -> bool {
+				self.terrain.walkable() && !self.contents.iter().any(|obj| obj.blocks_movement())
+			}
+
 			pub fn contains_wall(&self) -> bool {
 				for obj in &self.contents {
 					if matches!(obj, WorldObject::WALL) {
@@ -484,7 +1290,18 @@ pub mod world {
 						None => {}
 					}
 				}
-				' '
+				self.terrain.glyph()
+			}
+
+			pub fn get_fgcolor(&self) -> Color {
+				match self
+					.contents
+					.iter()
+					.find(|obj| !matches!(obj, WorldObject::ENTITY(_)))
+				{
+					Some(obj) => obj.get_fgcolor(),
+					None => self.terrain.fg_color(),
+				}
 			}
 
 			pub fn get_bgcolor(&self) -> Option<Color> {
@@ -502,19 +1319,212 @@ pub mod world {
 		pub enum WorldObject {
 			PLAYER,
 			WALL,
+			STAIRS,
+			UPSTAIRS,
+			/// An entity from the area's entity store
+			ENTITY(EntityId),
 		}
 
 		impl WorldObject {
+			/// Every object besides entities, each drawn from the definition with its id
+			pub const DEFINED: [WorldObject; 4] = [
+				WorldObject::PLAYER,
+				WorldObject::WALL,
+				WorldObject::STAIRS,
+				WorldObject::UPSTAIRS,
+			];
+
+			/// The object's key in the definitions, also used when saving
+			pub fn id(&self) -> &'static str {
+				match self {
+					WorldObject::PLAYER => "player",
+					WorldObject::WALL => "wall",
+					WorldObject::STAIRS => "stairs",
+					WorldObject::UPSTAIRS => "upstairs",
+					WorldObject::ENTITY(_) => "entity",
+				}
+			}
+
+			pub fn as_string(&self) -> String {
+				match self {
+					WorldObject::ENTITY(id) => format!("entity:{}", id),
+					_ => String::from(self.id()),
+				}
+			}
+
+			pub fn from_string(s: &str) -> Option<WorldObject> {
+				match s {
+					"player" => Some(WorldObject::PLAYER),
+					"wall" => Some(WorldObject::WALL),
+					"stairs" => Some(WorldObject::STAIRS),
+					"upstairs" => Some(WorldObject::UPSTAIRS),
+					_ => match s.strip_prefix("entity:") {
+						Some(id) => Some(WorldObject::ENTITY(id.parse().ok()?)),
+						None => None,
+					},
+				}
+			}
+
+			/// This object's entry in the loaded definitions, if it has one
+			pub fn definition(&self) -> Option<ObjectDefinition> {
+				self.with_definition(|definition| definition.clone())
+			}
+
+			/// The name of this object's definition, or its id without one
+			pub fn name(&self) -> String {
+				self.with_definition(|definition| definition.name.clone())
+					.unwrap_or_else(|| String::from(self.id()))
+			}
+
+			fn with_definition<T>(&self, f: impl FnOnce(&ObjectDefinition) -> T) -> Option<T> {
+				definitions().get(self.id()).map(f)
+			}
+
+			// Entities are drawn and checked through the area's entity store, so on
+			// their own they look like nothing and block nothing. Objects without a
+			// definition look and act the same way.
+
 			fn get_char(&self) -> Option<char> {
-				match &self {
-					WorldObject::PLAYER => Some('O'),
-					WorldObject::WALL => Some(' '),
+				match self {
+					WorldObject::ENTITY(_) => None,
+					_ => self.with_definition(|definition| definition.glyph),
 				}
 			}
+
+			fn blocks_sight(&self) -> bool {
+				match self {
+					WorldObject::ENTITY(_) => false,
+					_ => self
+						.with_definition(|definition| definition.blocks_sight)
+						.unwrap_or(false),
+				}
+			}
+
+			fn blocks_movement(&self) -> bool {
+				match self {
+					WorldObject::ENTITY(_) => false,
+					_ => self
+						.with_definition(|definition| definition.blocks_movement)
+						.unwrap_or(false),
+				}
+			}
+
+			fn get_fgcolor(&self) -> Color {
+				match self {
+					WorldObject::ENTITY(_) => Color::White,
+					_ => self
+						.with_definition(|definition| definition.fg_color)
+						.unwrap_or(Color::White),
+				}
+			}
+
 			fn get_bgcolor(&self) -> Color {
-				match &self {
-					WorldObject::WALL => Color::White,
-					_ => Color::Black,
+				match self {
+					WorldObject::ENTITY(_) => Color::Black,
+					_ => self
+						.with_definition(|definition| definition.bg_color)
+						.unwrap_or(Color::Black),
+				}
+			}
+		}
+
+		/// The ground of a tile, underneath its contents
+		#[derive(Copy, Clone, PartialEq)]
+		pub enum Terrain {
+			Floor,
+			Water,
+			Lava,
+			Rubble,
+			Grass,
+			Meadow,
+			Forest,
+			Mountain,
+		}
+
+		impl Terrain {
+			pub fn walkable(&self) -> bool {
+				!matches!(self, Terrain::Water | Terrain::Mountain)
+			}
+
+			pub fn transparent(&self) -> bool {
+				!matches!(self, Terrain::Grass | Terrain::Forest | Terrain::Mountain)
+			}
+
+			/// The number of turns it takes to step onto the terrain
+			pub fn movement_cost(&self) -> u32 {
+				match self {
+					Terrain::Rubble | Terrain::Forest => 2,
+					_ => 1,
+				}
+			}
+
+			/// The damage taken when stepping onto the terrain
+			pub fn damage(&self) -> i32 {
+				match self {
+					Terrain::Lava => 5,
+					_ => 0,
+				}
+			}
+
+			pub fn glyph(&self) -> char {
+				match self {
+					Terrain::Floor => ' ',
+					Terrain::Water | Terrain::Lava => '~',
+					Terrain::Rubble => ':',
+					Terrain::Grass => '"',
+					Terrain::Meadow => '.',
+					Terrain::Forest => '♣',
+					Terrain::Mountain => '^',
+				}
+			}
+
+			pub fn fg_color(&self) -> Color {
+				match self {
+					Terrain::Floor => Color::White,
+					Terrain::Water => Color::White,
+					Terrain::Lava => Color::Yellow,
+					Terrain::Rubble => Color::LightGray,
+					Terrain::Grass | Terrain::Meadow | Terrain::Forest => Color::Green,
+					Terrain::Mountain => Color::LightGray,
+				}
+			}
+
+			pub fn bg_color(&self) -> Color {
+				match self {
+					Terrain::Water => Color::Blue,
+					Terrain::Lava => Color::Red,
+					_ => Color::Gray,
+				}
+			}
+
+			pub fn name(&self) -> &'static str {
+				match self {
+					Terrain::Floor => "floor",
+					Terrain::Water => "water",
+					Terrain::Lava => "lava",
+					Terrain::Rubble => "rubble",
+					Terrain::Grass => "grass",
+					Terrain::Meadow => "meadow",
+					Terrain::Forest => "forest",
+					Terrain::Mountain => "mountain",
+				}
+			}
+
+			pub fn as_string(&self) -> String {
+				String::from(self.name())
+			}
+
+			pub fn from_string(s: &str) -> Option<Terrain> {
+				match s {
+					"floor" => Some(Terrain::Floor),
+					"water" => Some(Terrain::Water),
+					"lava" => Some(Terrain::Lava),
+					"rubble" => Some(Terrain::Rubble),
+					"grass" => Some(Terrain::Grass),
+					"meadow" => Some(Terrain::Meadow),
+					"forest" => Some(Terrain::Forest),
+					"mountain" => Some(Terrain::Mountain),
+					_ => None,
 				}
 			}
 		}
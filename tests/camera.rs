@@ -0,0 +1,118 @@
+use roguelike::render::camera::{Camera, CameraMode};
+use roguelike::render::viewport::Viewport;
+use roguelike::{Action, Coordinate, Simulation};
+
+#[test]
+fn centered_camera_follows_exactly() {
+	let mut camera = Camera::new(CameraMode::Centered, Coordinate::new(0, 0));
+	camera.follow(Coordinate::new(7, -3), &[], 40, 20);
+	assert!(camera.center == Coordinate::new(7, -3));
+}
+
+#[test]
+fn dead_zone_camera_scrolls_only_at_the_edges() {
+	let mode = CameraMode::DeadZone {
+		width: 10,
+		height: 6,
+	};
+	let mut camera = Camera::new(mode, Coordinate::new(0, 0));
+	camera.follow(Coordinate::new(4, 2), &[], 40, 20);
+	assert!(camera.center == Coordinate::new(0, 0));
+	camera.follow(Coordinate::new(8, -5), &[], 40, 20);
+	assert!(camera.center == Coordinate::new(3, -2));
+}
+
+#[test]
+fn room_camera_frames_rooms_that_fit() {
+	let rooms = [
+		[Coordinate::new(0, 0), Coordinate::new(10, 6)],
+		[Coordinate::new(20, 0), Coordinate::new(80, 30)],
+	];
+	let mut camera = Camera::new(CameraMode::Room, Coordinate::new(0, 0));
+	camera.follow(Coordinate::new(2, 2), &rooms, 40, 20);
+	assert!(camera.center == Coordinate::new(5, 3));
+	camera.follow(Coordinate::new(30, 10), &rooms, 40, 20);
+	assert!(camera.center == Coordinate::new(30, 10));
+	camera.follow(Coordinate::new(15, 3), &rooms, 40, 20);
+	assert!(camera.center == Coordinate::new(15, 3));
+}
+
+#[test]
+fn clicks_follow_a_resized_canvas_and_camera() {
+	let mut simulation = Simulation::new(7);
+	simulation.player.resize(60, 30);
+	simulation.perform(Action::CycleCamera);
+	simulation.perform(Action::CycleCamera);
+	assert!(simulation.player.camera.mode == CameraMode::Room);
+	assert_eq!(simulation.player.canvas.width, 60);
+	let canvas = simulation.player.canvas.clone();
+	let (x, y, target) = (0..canvas.width)
+		.flat_map(|x| (0..canvas.height).map(move |y| (x, y)))
+		.find_map(|(x, y)| match canvas.get(x, y).on_click {
+			Action::Move(coord) => Some((x, y, coord)),
+			_ => None,
+		})
+		.unwrap();
+	simulation.click(x, y);
+	assert!(simulation.player.location == target);
+	assert!(simulation.screen().contains("Camera: room."));
+}
+
+#[test]
+fn viewport_maps_both_ways() {
+	let viewport = Viewport::new(
+		Coordinate::new(1, 9),
+		Coordinate::new(75, 49),
+		Coordinate::new(100, 20),
+	);
+	assert_eq!((viewport.width(), viewport.height()), (74, 40));
+	let screen = viewport.to_screen(Coordinate::new(100, 20)).unwrap();
+	assert!(screen == Coordinate::new(38, 29));
+	for (x, y) in [(1, 9), (74, 48), (38, 29), (10, 40)] {
+		let world = viewport.to_world(Coordinate::new(x, y)).unwrap();
+		assert!(viewport.to_screen(world).unwrap() == Coordinate::new(x, y));
+	}
+	assert!(viewport.to_world(Coordinate::new(75, 20)).is_none());
+	assert!(viewport.to_world(Coordinate::new(0, 20)).is_none());
+	assert!(viewport.to_screen(Coordinate::new(0, 0)).is_none());
+}
+
+#[test]
+fn viewport_matches_the_drawn_map() {
+	let simulation = Simulation::new(3);
+	let player = &simulation.player;
+	assert!(player.viewport.to_screen(player.location).is_some());
+	let mut moves = 0;
+	for x in 0..player.canvas.width {
+		for y in 0..player.canvas.height {
+			if let Action::Move(coord) = player.canvas.get(x, y).on_click {
+				assert!(player.viewport.to_world(Coordinate::new(x, y)).unwrap() == coord);
+				moves += 1;
+			}
+		}
+	}
+	assert!(moves > 0);
+}
+
+#[test]
+fn the_camera_survives_save_and_load() {
+	let mut simulation = Simulation::new(7);
+	simulation.perform(Action::CycleCamera);
+	let center = Coordinate::new(
+		simulation.player.location.x + 3,
+		simulation.player.location.y - 2,
+	);
+	simulation.player.camera.center = center;
+	let state = simulation.player.save_state();
+
+	let mut loaded = Simulation::new(3);
+	loaded.player.load_state(&state).unwrap();
+	assert!(loaded.player.camera.mode == simulation.player.camera.mode);
+	assert!(matches!(
+		loaded.player.camera.mode,
+		CameraMode::DeadZone { .. }
+	));
+	assert!(loaded.player.camera.center == center);
+	loaded.perform(Action::None);
+	assert!(loaded.player.camera.center == center);
+}
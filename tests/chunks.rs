@@ -0,0 +1,140 @@
+use roguelike::chunks::chunks::{ChunkedWorld, CHUNK_SIZE};
+use roguelike::engine::engine::Player;
+use roguelike::world::world::area::{Area, Tile};
+use roguelike::{Action, Coordinate, Simulation};
+
+/// The glyph, wall and stairs of every tile in a chunk
+fn chunk_tiles(area: &Area, chunk: (i32, i32)) -> Vec<(char, bool, bool)> {
+	let mut tiles = vec![];
+	for x in chunk.0 * CHUNK_SIZE..(chunk.0 + 1) * CHUNK_SIZE {
+		for y in chunk.1 * CHUNK_SIZE..(chunk.1 + 1) * CHUNK_SIZE {
+			assert!(area.tile_exists(x, y));
+			let tile = area.get_tile_at(x, y);
+			tiles.push((
+				tile.get_char(),
+				tile.contains_wall(),
+				tile.contains_stairs(),
+			));
+		}
+	}
+	tiles
+}
+
+#[test]
+fn chunks_are_loaded_around_the_center_and_evicted_when_far() {
+	let mut world = ChunkedWorld::new(7);
+	world.load_radius = 1;
+	world.keep_radius = 2;
+	let mut area = Area::new(None);
+	world.update(&mut area, Coordinate::new(0, 0));
+	assert_eq!(world.loaded_count(), 9);
+	assert!(world.is_loaded((-1, -1)) && world.is_loaded((1, 1)));
+	assert_eq!(area.tile_count(), 9 * CHUNK_SIZE * CHUNK_SIZE);
+	let before = chunk_tiles(&area, (0, 0));
+
+	world.update(&mut area, Coordinate::new(CHUNK_SIZE * 10, 0));
+	assert!(world.is_evicted((0, 0)));
+	assert!(!area.tile_exists(0, 0));
+	assert_eq!(world.loaded_count(), 9);
+	assert_eq!(area.tile_count(), 9 * CHUNK_SIZE * CHUNK_SIZE);
+
+	world.update(&mut area, Coordinate::new(0, 0));
+	assert!(world.is_loaded((0, 0)));
+	assert_eq!(chunk_tiles(&area, (0, 0)), before);
+}
+
+#[test]
+fn chunks_are_the_same_whichever_way_they_are_reached() {
+	let mut direct = ChunkedWorld::new(3);
+	let mut direct_area = Area::new(None);
+	direct.update(&mut direct_area, Coordinate::new(100, -40));
+
+	let mut roundabout = ChunkedWorld::new(3);
+	let mut roundabout_area = Area::new(None);
+	for x in (-200..=100).step_by(CHUNK_SIZE as usize) {
+		roundabout.update(&mut roundabout_area, Coordinate::new(x, -40));
+	}
+	let chunk = ChunkedWorld::chunk_of(Coordinate::new(100, -40));
+	assert_eq!(
+		chunk_tiles(&direct_area, chunk),
+		chunk_tiles(&roundabout_area, chunk)
+	);
+	assert_ne!(
+		chunk_tiles(&direct_area, chunk),
+		chunk_tiles(&direct_area, (chunk.0 + 1, chunk.1))
+	);
+}
+
+#[test]
+fn entities_are_back_on_their_tiles_after_eviction() {
+	let mut world = ChunkedWorld::new(5);
+	let mut area = Area::new(None);
+	world.update(&mut area, Coordinate::new(0, 0));
+	let spot = (0..CHUNK_SIZE)
+		.flat_map(|x| (0..CHUNK_SIZE).map(move |y| Coordinate::new(x, y)))
+		.find(|spot| area.is_walkable_at(spot.x, spot.y))
+		.unwrap();
+	let rat = area.spawn("rat", spot).unwrap();
+	let gold = area.entities.create();
+	area.give(rat, gold);
+	area.entities.health.get_mut(&rat).unwrap().current = 1;
+	world.update(&mut area, Coordinate::new(CHUNK_SIZE * 20, 0));
+	assert!(!area.entities.ids().contains(&rat));
+	assert!(!area.entities.ids().contains(&gold));
+
+	let mut loaded = ChunkedWorld::from_json(&world.to_json()).unwrap();
+	loaded.update(&mut area, Coordinate::new(0, 0));
+	assert_eq!(area.entities_at(spot.x, spot.y), &[rat]);
+	assert!(!area.is_walkable_at(spot.x, spot.y));
+	assert_eq!(area.entities.health[&rat].current, 1);
+	assert_eq!(area.entities.inventories[&rat], vec![gold]);
+}
+
+#[test]
+fn objects_carved_into_a_chunk_survive_eviction() {
+	let mut world = ChunkedWorld::new(5);
+	let mut area = Area::new(None);
+	world.update(&mut area, Coordinate::new(0, 0));
+	area.set_tile(1, 1, Tile::up_stairs());
+	area.set_tile(2, 1, Tile::stairs());
+	area.set_tile(3, 1, Tile::wall());
+	let before = chunk_tiles(&area, (0, 0));
+	world.update(&mut area, Coordinate::new(CHUNK_SIZE * 20, 0));
+	world.update(&mut area, Coordinate::new(0, 0));
+	assert!(area.get_tile_at(1, 1).contains_up_stairs());
+	assert_eq!(chunk_tiles(&area, (0, 0)), before);
+}
+
+#[test]
+fn the_player_streams_chunks_while_exploring() {
+	let mut simulation = Simulation {
+		player: Player::in_world(ChunkedWorld::new(11)),
+	};
+	let spawn = simulation.player.location;
+	assert!(simulation.player.area.is_walkable_at(spawn.x, spawn.y));
+	// there's always more to explore in an open world, so only keep going for a while
+	simulation.perform(Action::Explore);
+	for _ in 0..8 {
+		if simulation.player.activity.is_none() {
+			simulation.perform(Action::Explore);
+		}
+		simulation.perform(Action::Resume);
+	}
+	let location = simulation.player.location;
+	assert!(ChunkedWorld::chunk_of(location) != ChunkedWorld::chunk_of(spawn));
+	let world = simulation.player.world.as_ref().unwrap();
+	let (cx, cy) = ChunkedWorld::chunk_of(location);
+	for dx in -2..=2 {
+		for dy in -2..=2 {
+			assert!(world.is_loaded((cx + dx, cy + dy)));
+		}
+	}
+
+	let state = simulation.player.save_state();
+	let mut loaded = Player::in_world(ChunkedWorld::new(0));
+	loaded.load_state(&state).unwrap();
+	let loaded_world = loaded.world.as_ref().unwrap();
+	assert_eq!(loaded_world.seed, 11);
+	assert_eq!(loaded_world.loaded_count(), world.loaded_count());
+	assert!(loaded.location == location);
+}
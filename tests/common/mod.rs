@@ -0,0 +1,70 @@
+//! Fixtures shared by the integration tests, which each use only some of them
+#![allow(dead_code)]
+
+use roguelike::dungeon::dungeon::Dungeon;
+use roguelike::engine::engine::Player;
+use roguelike::world::world::area::Area;
+use roguelike::world::world::region::Region;
+use roguelike::{Action, Coordinate, Simulation};
+use std::collections::{HashSet, VecDeque};
+
+/// A room `width` tiles wide and 5 high inside its walls, with nothing in it giving off light
+pub fn room(width: i32) -> Area {
+	let mut area = Area::new(None);
+	area.place_region(&Region::new(width + 2, 7, Coordinate::new(0, 0)));
+	area
+}
+
+/// The player on the left of a `room`, carrying a torch of the given radius
+pub fn simulation(area: Area, torch_radius: i32) -> Simulation {
+	let mut player = Player::new(Dungeon {
+		area,
+		spawn_point: Coordinate::new(2, 3),
+	});
+	player.torch_radius = torch_radius;
+	let mut simulation = Simulation { player };
+	simulation.perform(Action::None);
+	simulation
+}
+
+/// A 9x5 room with a goblin and a pile of gold in it, and the player on its left
+pub fn goblin_and_gold() -> Simulation {
+	let mut area = room(9);
+	area.spawn("goblin", Coordinate::new(8, 3)).unwrap();
+	area.spawn("gold", Coordinate::new(8, 4)).unwrap();
+	simulation(area, 8)
+}
+
+/// Every walkable tile that can be reached from `start`
+pub fn reachable(area: &Area, start: Coordinate) -> HashSet<(i32, i32)> {
+	let mut seen = HashSet::from([start.as_tuple()]);
+	let mut queue = VecDeque::from([start.as_tuple()]);
+	while let Some((x, y)) = queue.pop_front() {
+		for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+			let next = (x + dx, y + dy);
+			if area.tile_exists(next.0, next.1)
+				&& area.is_walkable_at(next.0, next.1)
+				&& seen.insert(next)
+			{
+				queue.push_back(next);
+			}
+		}
+	}
+	seen
+}
+
+/// Panics if there's any floor that can't be walked to from the spawn point
+pub fn assert_connected(dungeon: &Dungeon, description: &str) {
+	let floor = reachable(&dungeon.area, dungeon.spawn_point);
+	for (x, x_col) in &dungeon.area.map {
+		for (y, tile) in x_col {
+			assert!(
+				tile.contains_wall() || floor.contains(&(*x, *y)),
+				"{} leaves ({}, {}) cut off",
+				description,
+				x,
+				y
+			);
+		}
+	}
+}
@@ -0,0 +1,82 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use roguelike::content::definitions::{
+	definitions, load_definitions, required, DefinitionKind, Definitions,
+};
+use roguelike::dungeon::dungeon::{Dungeon, DungeonConfig};
+use roguelike::render::canvas::Color;
+
+#[test]
+fn bundled_definitions_are_valid() {
+	let definitions = Definitions::from_json(include_str!("../data/objects.json")).unwrap();
+	for id in ["player", "wall", "stairs"] {
+		assert!(definitions.get(id).is_some(), "missing \"{}\"", id);
+	}
+	assert!(!definitions.of_kind(DefinitionKind::Monster).is_empty());
+	assert!(!definitions.of_kind(DefinitionKind::Item).is_empty());
+}
+
+#[test]
+fn definitions_default_and_parse_colors() {
+	let definitions = Definitions::from_json(
+		r##"{
+			"monsters": {
+				"bat": { "glyph": "b", "fg": "#f80", "health": 2, "spawn": { "weight": 0.5 } }
+			},
+			"items": { "gem": { "name": "emerald", "glyph": "*", "bg": "green" } }
+		}"##,
+	)
+	.unwrap();
+	let bat = definitions.get("bat").unwrap();
+	assert_eq!(bat.name, "bat");
+	assert!(matches!(bat.fg_color, Color::Rgb(255, 136, 0)));
+	assert!(matches!(bat.bg_color, Color::Black));
+	assert!(!bat.blocks_movement && !bat.blocks_sight);
+	assert_eq!(bat.stats.health, 2);
+	let spawn = bat.spawn.unwrap();
+	assert_eq!((spawn.weight, spawn.cost, spawn.min_depth), (0.5, 1, 1));
+	let gem = definitions.get("gem").unwrap();
+	assert_eq!(gem.name, "emerald");
+	assert!(matches!(gem.bg_color, Color::Green));
+	assert!(gem.kind == DefinitionKind::Item);
+	assert!(gem.spawn.is_none());
+}
+
+#[test]
+fn invalid_definitions_are_rejected() {
+	for text in [
+		r#"{ "objects": { "wall": { "glyph": "ab" } } }"#,
+		r#"{ "objects": { "wall": { "glyph": "w", "bg": "12" } } }"#,
+		r#"{ "objects": { "wall": { "glyph": "w", "blocks_sight": 1 } } }"#,
+		r#"{ "monsters": { "rat": { "glyph": "r", "spawn": { "weight": 0 } } } }"#,
+		r#"{ "monsters": { "rat": { "glyph": "r", "spawn": { "weight": 1, "cost": 0 } } } }"#,
+		r#"{ "objects": { "rat": { "glyph": "r" } }, "monsters": { "rat": { "glyph": "r" } } }"#,
+		"not json",
+	] {
+		assert!(Definitions::from_json(text).is_err(), "accepted {}", text);
+	}
+}
+
+#[test]
+fn definitions_missing_anything_required_are_not_loaded() {
+	for id in required() {
+		let mut value = json::parse(include_str!("../data/objects.json")).unwrap();
+		for section in ["objects", "monsters", "items"] {
+			value[section].remove(&id);
+		}
+		let error = load_definitions(&value.dump()).unwrap_err();
+		assert!(error.contains(&id), "{}", error);
+		assert!(definitions().get(&id).is_some());
+	}
+}
+
+#[test]
+fn definitions_generators_spawn_can_be_left_out() {
+	let bundled = include_str!("../data/objects.json");
+	let mut value = json::parse(bundled).unwrap();
+	value["objects"].remove("torch");
+	load_definitions(&value.dump()).unwrap();
+	let dungeon = Dungeon::new(DungeonConfig::default(), &mut StdRng::seed_from_u64(1));
+	load_definitions(bundled).unwrap();
+	assert!(dungeon.unwrap().area.entities.ids().is_empty());
+}
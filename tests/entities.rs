@@ -0,0 +1,95 @@
+mod common;
+
+use common::{room, simulation};
+use roguelike::entity::store::{Ai, Health};
+use roguelike::world::world::area::{Area, WorldObject};
+use roguelike::{Action, Coordinate};
+
+#[test]
+fn entities_are_indexed_by_position_and_referenced_by_tiles() {
+	let mut area = room(9);
+	let rat = area.spawn("rat", Coordinate::new(4, 3)).unwrap();
+	let gold = area.spawn("gold", Coordinate::new(4, 3)).unwrap();
+	assert_eq!(area.entities_at(4, 3), &[rat, gold]);
+	assert!(area.entities.health.contains_key(&rat));
+	assert!(!area.entities.health.contains_key(&gold));
+
+	area.place_entity(rat, Coordinate::new(6, 2));
+	assert_eq!(area.entities_at(4, 3), &[gold]);
+	assert_eq!(area.entities_at(6, 2), &[rat]);
+	assert!(area
+		.get_tile_at(6, 2)
+		.contents
+		.iter()
+		.any(|obj| matches!(obj, WorldObject::ENTITY(id) if *id == rat)));
+	assert!(!area
+		.get_tile_at(4, 3)
+		.contents
+		.iter()
+		.any(|obj| matches!(obj, WorldObject::ENTITY(id) if *id == rat)));
+
+	area.remove_entity(gold);
+	assert!(area.entities_at(4, 3).is_empty());
+	assert!(area.get_tile_at(4, 3).contents.is_empty());
+	assert!(area.spawn("dragon", Coordinate::new(4, 3)).is_none());
+}
+
+#[test]
+fn monsters_are_drawn_and_block_movement() {
+	let mut area = room(9);
+	area.spawn("goblin", Coordinate::new(5, 3)).unwrap();
+	let mut simulation = simulation(area, 8);
+	assert!(simulation.screen().contains('g'));
+	simulation.perform(Action::Move(Coordinate::new(8, 3)));
+	assert!(simulation.player.location == Coordinate::new(4, 3));
+	assert!(simulation.screen().contains("The goblin blocks your way."));
+}
+
+#[test]
+fn items_can_be_walked_over() {
+	let mut area = room(9);
+	area.spawn("gold", Coordinate::new(5, 3)).unwrap();
+	let mut simulation = simulation(area, 8);
+	simulation.perform(Action::Move(Coordinate::new(8, 3)));
+	assert!(simulation.player.location == Coordinate::new(5, 3));
+	simulation.perform(Action::Move(Coordinate::new(8, 3)));
+	assert!(simulation.player.location == Coordinate::new(8, 3));
+}
+
+#[test]
+fn entities_survive_save_and_load() {
+	let mut area = room(9);
+	let ogre = area.spawn("ogre", Coordinate::new(5, 3)).unwrap();
+	let sword = area.spawn("sword", Coordinate::new(7, 3)).unwrap();
+	area.give(ogre, sword);
+	area.entities.health.get_mut(&ogre).unwrap().current = 3;
+	let saved = area.to_json();
+
+	let loaded = Area::from_json(&saved).unwrap();
+	assert_eq!(loaded.entities_at(5, 3), &[ogre]);
+	assert!(loaded.entities_at(7, 3).is_empty());
+	assert!(loaded.get_tile_at(7, 3).contents.is_empty());
+	assert_eq!(loaded.entities.inventories[&ogre], vec![sword]);
+	assert_eq!(loaded.entities.health[&ogre].current, 3);
+	assert_eq!(loaded.entities.name(sword), "sword");
+	assert_eq!(loaded.appearance_at(5, 3).0, 'G');
+	assert!(!loaded.is_walkable_at(5, 3));
+}
+
+#[test]
+fn entities_made_of_any_components_survive_save_and_load() {
+	let mut area = room(9);
+	let spirit = area.entities.create();
+	area.entities
+		.health
+		.insert(spirit, Health { current: 2, max: 5 });
+	area.entities.ai.insert(spirit, Ai::Wander);
+	let light = area.entities.create();
+	area.entities.lights.insert(light, 3);
+	assert_eq!(area.entities.ids(), vec![spirit, light]);
+
+	let loaded = Area::from_json(&area.to_json()).unwrap();
+	assert_eq!(loaded.entities.health[&spirit].max, 5);
+	assert!(matches!(loaded.entities.ai[&spirit], Ai::Wander));
+	assert_eq!(loaded.entities.lights[&light], 3);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{room, simulation};
+use roguelike::dungeon::dungeon::DungeonConfig;
+use roguelike::engine::engine::STEPS_PER_ACTION;
+use roguelike::{Action, Coordinate, Simulation};
+
+/// Explore, resuming until the activity ends
+fn explore(simulation: &mut Simulation) {
+	simulation.perform(Action::Explore);
+	while simulation.player.activity.is_some() {
+		simulation.perform(Action::Resume);
+	}
+}
+
+#[test]
+fn explore_uncovers_the_whole_room() {
+	let mut simulation = simulation(room(23), 3);
+	assert!(!simulation.player.memory.remembers(22, 3));
+	explore(&mut simulation);
+	for x in 0..25 {
+		for y in 0..7 {
+			assert!(
+				simulation.player.memory.remembers(x, y)
+					|| simulation.player.location == Coordinate::new(x, y)
+			);
+		}
+	}
+	assert!(simulation
+		.screen()
+		.contains("There is nothing left to explore."));
+	let location = simulation.player.location;
+	let outcome = simulation.run(&[Action::Explore]);
+	assert!(simulation.player.location == location);
+	assert_eq!(outcome.frames, 1);
+}
+
+#[test]
+fn explore_stops_for_monsters() {
+	let mut area = room(23);
+	area.spawn("rat", Coordinate::new(20, 3)).unwrap();
+	let mut simulation = simulation(area, 3);
+	explore(&mut simulation);
+	assert!(simulation.screen().contains("You notice a rat."));
+	assert!(!simulation
+		.screen()
+		.contains("There is nothing left to explore."));
+	assert!(simulation.player.location.x < 20);
+}
+
+#[test]
+fn explore_covers_a_generated_dungeon() {
+	let mut simulation = Simulation::with_config(
+		5,
+		DungeonConfig {
+			dungeon_width: 80,
+			dungeon_height: 30,
+			max_split_iterations: 3,
+			..Default::default()
+		},
+	)
+	.unwrap();
+	for _ in 0..20 {
+		explore(&mut simulation);
+		if simulation
+			.screen()
+			.contains("There is nothing left to explore.")
+		{
+			break;
+		}
+	}
+	assert!(simulation
+		.screen()
+		.contains("There is nothing left to explore."));
+	let rooms = simulation.player.area.rooms.clone();
+	for room in rooms {
+		assert!(simulation
+			.player
+			.memory
+			.remembers(room[0].x + room[1].x / 2 - room[0].x / 2, room[0].y + 1));
+	}
+}
+
+#[test]
+fn long_explorations_pause_between_batches_of_steps() {
+	let mut simulation = simulation(room(23), 1);
+	let outcome = simulation.run(&[Action::Explore]);
+	assert!(outcome.frames <= STEPS_PER_ACTION + 1);
+	assert!(simulation.player.activity.is_some());
+	let location = simulation.player.location;
+	simulation.perform(Action::Resume);
+	assert!(simulation.player.location != location);
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use common::{assert_connected, reachable};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use roguelike::dungeon::dungeon::{Dungeon, DungeonConfig};
+use roguelike::world::world::area::{Area, Hallway, HallwayStyle, Tile};
+use roguelike::world::world::region::Region;
+use roguelike::Coordinate;
+
+fn styled(seed: u64, style: HallwayStyle) -> Dungeon {
+	let config = DungeonConfig {
+		dungeon_width: 80,
+		dungeon_height: 40,
+		terrain_feature_chance: 0.0,
+		hallway_styles: vec![(style, 1.0)],
+		..Default::default()
+	};
+	Dungeon::new(config, &mut StdRng::seed_from_u64(seed)).unwrap()
+}
+
+/// Whether a tile is floor outside of every room
+fn is_corridor(dungeon: &Dungeon, x: i32, y: i32) -> bool {
+	dungeon.area.tile_exists(x, y)
+		&& dungeon.area.is_walkable_at(x, y)
+		&& dungeon
+			.area
+			.rooms
+			.iter()
+			.all(|[min, max]| x < min.x || x > max.x || y < min.y || y > max.y)
+}
+
+/// How many 2x2 squares of corridor there are, and how many corridor tiles turn a corner
+fn corridor_shape(dungeon: &Dungeon) -> (usize, usize) {
+	let [min, max] = dungeon.area.bounds().unwrap();
+	let (mut squares, mut corners) = (0, 0);
+	for x in min.x..=max.x {
+		for y in min.y..=max.y {
+			if !is_corridor(dungeon, x, y) {
+				continue;
+			}
+			if [(1, 0), (0, 1), (1, 1)]
+				.iter()
+				.all(|(dx, dy)| is_corridor(dungeon, x + dx, y + dy))
+			{
+				squares += 1;
+			}
+			let open = |dx: i32, dy: i32| dungeon.area.is_walkable_at(x + dx, y + dy);
+			let (horizontal, vertical) = (open(1, 0) || open(-1, 0), open(0, 1) || open(0, -1));
+			if horizontal && vertical {
+				corners += 1;
+			}
+		}
+	}
+	(squares, corners)
+}
+
+/// The glyph of every tile in a region
+fn glyphs(area: &Area, region: &Region) -> Vec<char> {
+	let mut glyphs = vec![];
+	for x in region.position.x..=region.get_edge_x() {
+		for y in region.position.y..=region.get_top_y() {
+			glyphs.push(area.get_tile_at(x, y).get_char());
+		}
+	}
+	glyphs
+}
+
+#[test]
+fn blocked_rooms_are_joined_around_what_is_between_them() {
+	let mut area = Area::new(None);
+	let left = Region::new(8, 8, Coordinate::new(0, 0));
+	let right = Region::new(8, 8, Coordinate::new(30, 0));
+	let between = Region::new(8, 18, Coordinate::new(12, -5));
+	for region in [&left, &right, &between] {
+		area.place_region(region);
+	}
+	let before = glyphs(&area, &between);
+	assert!(area.get_valid_hallways(&left, &right).is_empty());
+
+	area.create_hallway(&left, &right, &mut StdRng::seed_from_u64(1));
+	let floor = reachable(&area, left.get_center());
+	assert!(floor.contains(&right.get_center().as_tuple()));
+	assert!(glyphs(&area, &between) == before);
+}
+
+#[test]
+fn routed_hallways_go_around_other_rooms() {
+	let mut area = Area::new(None);
+	let left = Region::new(8, 8, Coordinate::new(0, 0));
+	let right = Region::new(8, 8, Coordinate::new(30, 0));
+	// a room without walls, whose floor costs nothing to walk through unless rooms are avoided
+	let between = Region::new(8, 14, Coordinate::new(12, -3));
+	area.place_region(&left);
+	area.place_region(&right);
+	area.fill(
+		between.position,
+		Coordinate::new(between.get_edge_x(), between.get_top_y()),
+		Tile::new(None),
+	);
+	for region in [&left, &right, &between] {
+		area.add_room(region);
+	}
+
+	match area.route_hallway(&left, &right) {
+		Hallway::ROUTED { path } => {
+			assert!(path.last() == Some(&right.get_center()));
+			assert!(path
+				.iter()
+				.all(|position| !between.overlaps_coordinate(*position)));
+		}
+		_ => panic!("routing gave a hallway that isn't routed"),
+	}
+}
+
+#[test]
+fn small_rooms_in_large_leaves_always_connect() {
+	for seed in 1..=30 {
+		// rooms this small often don't line up well enough for a straight or bent hallway
+		let config = DungeonConfig {
+			min_room_width: 3,
+			min_room_height: 3,
+			max_room_area: Some(16),
+			terrain_feature_chance: 0.0,
+			..Default::default()
+		};
+		let dungeon = Dungeon::new(config, &mut StdRng::seed_from_u64(seed)).unwrap();
+		assert_connected(&dungeon, &format!("seed {}", seed));
+	}
+}
+
+#[test]
+fn every_hallway_style_connects() {
+	for style in [
+		HallwayStyle::Narrow,
+		HallwayStyle::Wide,
+		HallwayStyle::Diagonal,
+		HallwayStyle::Winding,
+	] {
+		for seed in 1..=5 {
+			let dungeon = styled(seed, style);
+			assert_connected(
+				&dungeon,
+				&format!("{:?} hallways with seed {}", style, seed),
+			);
+		}
+	}
+}
+
+#[test]
+fn hallway_styles_change_the_shape_of_corridors() {
+	let (narrow_squares, narrow_corners) = corridor_shape(&styled(2, HallwayStyle::Narrow));
+	assert_eq!(narrow_squares, 0);
+	let (wide_squares, _) = corridor_shape(&styled(2, HallwayStyle::Wide));
+	assert!(wide_squares > 0);
+	for style in [HallwayStyle::Diagonal, HallwayStyle::Winding] {
+		let (_, corners) = corridor_shape(&styled(2, style));
+		assert!(
+			corners > narrow_corners * 2,
+			"{:?} corridors hardly turn",
+			style
+		);
+	}
+}
+
+#[test]
+fn wide_hallways_are_made_of_side_by_side_lanes() {
+	let mut area = Area::new(None);
+	let left = Region::new(8, 8, Coordinate::new(0, 0));
+	let right = Region::new(8, 8, Coordinate::new(14, 0));
+	area.place_region(&left);
+	area.place_region(&right);
+	let mut rng = StdRng::seed_from_u64(3);
+	let hallways = area.get_valid_styled_hallways(&left, &right, HallwayStyle::Wide, &mut rng);
+	assert!(!hallways.is_empty());
+	for hallway in &hallways {
+		match hallway {
+			Hallway::WIDE { lanes } => assert!(lanes.len() == 2 || lanes.len() == 3),
+			_ => panic!("a narrow hallway was given where wide ones fit"),
+		}
+	}
+	area.create_hallway_from_valid(&hallways, &mut rng);
+	let floor = reachable(&area, left.get_center());
+	assert!(floor.contains(&right.get_center().as_tuple()));
+}
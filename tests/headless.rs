@@ -0,0 +1,90 @@
+mod common;
+
+use common::{room, simulation};
+use roguelike::world::world::area::{Terrain, Tile};
+use roguelike::{Action, Coordinate, Simulation};
+
+#[test]
+fn same_seed_renders_same_screen() {
+	assert_eq!(Simulation::new(7).screen(), Simulation::new(7).screen());
+}
+
+#[test]
+fn clicking_a_highlighted_tile_moves_the_player() {
+	let mut simulation = Simulation::new(7);
+	let spawn = simulation.player.location;
+	let canvas = simulation.player.canvas.clone();
+	let (x, y, target) = (0..canvas.width)
+		.flat_map(|x| (0..canvas.height).map(move |y| (x, y)))
+		.find_map(|(x, y)| match canvas.get(x, y).on_click {
+			Action::Move(coord) => Some((x, y, coord)),
+			_ => None,
+		})
+		.unwrap();
+	simulation.click(x, y);
+	assert!(simulation.player.location == target);
+	assert!(simulation.player.location != spawn);
+	assert!(simulation.player.turn > 0);
+}
+
+#[test]
+fn moves_into_walls_are_ignored() {
+	let mut simulation = simulation(room(9), 8);
+	let before = simulation.run(&[Action::Move(Coordinate::new(1, 3))]);
+	assert!(simulation.player.location == Coordinate::new(1, 3));
+	assert_eq!(before.state["turn"].as_u32(), Some(1));
+	let outcome = simulation.run(&[Action::Move(Coordinate::new(0, 3))]);
+	assert!(simulation.player.location == Coordinate::new(1, 3));
+	assert_eq!(outcome.frames, 1);
+	assert_eq!(outcome.state["turn"], before.state["turn"]);
+}
+
+/// A 9x5 room with a column of `terrain` in the middle, and the player on its left
+fn terrain_room(terrain: Terrain) -> Simulation {
+	let mut area = room(9);
+	area.fill(
+		Coordinate::new(5, 1),
+		Coordinate::new(5, 5),
+		Tile::terrain(terrain),
+	);
+	simulation(area, 8)
+}
+
+#[test]
+fn water_blocks_movement() {
+	let mut simulation = terrain_room(Terrain::Water);
+	simulation.perform(Action::Move(Coordinate::new(8, 3)));
+	assert!(simulation.player.location == Coordinate::new(4, 3));
+	assert!(simulation.screen().contains("The water blocks your way."));
+}
+
+#[test]
+fn lava_damages_on_enter() {
+	let mut simulation = terrain_room(Terrain::Lava);
+	let health = simulation.player.health;
+	simulation.perform(Action::Move(Coordinate::new(8, 3)));
+	assert!(simulation.player.location == Coordinate::new(5, 3));
+	assert_eq!(simulation.player.health, health - Terrain::Lava.damage());
+}
+
+#[test]
+fn rubble_costs_extra_turns() {
+	let mut simulation = terrain_room(Terrain::Rubble);
+	simulation.perform(Action::Move(Coordinate::new(8, 3)));
+	assert_eq!(simulation.player.turn, 5 + Terrain::Rubble.movement_cost());
+}
+
+#[test]
+fn grass_blocks_sight() {
+	let simulation = terrain_room(Terrain::Grass);
+	assert!(!simulation.player.memory.remembers(8, 3));
+	assert!(simulation.player.memory.remembers(5, 3));
+}
+
+#[test]
+fn players_see_out_of_grass_they_stand_in() {
+	let mut simulation = terrain_room(Terrain::Grass);
+	simulation.perform(Action::Move(Coordinate::new(5, 3)));
+	assert!(simulation.player.location == Coordinate::new(5, 3));
+	assert!(simulation.player.memory.remembers(8, 3));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{room, simulation};
+use roguelike::light::light_map::LightMap;
+use roguelike::render::canvas::Color;
+use roguelike::{Action, Coordinate};
+
+#[test]
+fn light_fades_and_stops_at_walls() {
+	let area = room(23);
+	let light = LightMap::compute(&area, &[(Coordinate::new(2, 3), 6)]);
+	assert_eq!(light.level_at(2, 3), 1.0);
+	assert_eq!(light.level_at(4, 3), 1.0);
+	assert!(light.level_at(7, 3) < 1.0);
+	assert!(light.is_lit(8, 3));
+	assert!(!light.is_lit(9, 3));
+	assert!(light.is_lit(0, 3));
+	assert!(!light.is_lit(-1, 3));
+}
+
+#[test]
+fn unlit_tiles_are_not_seen() {
+	let mut simulation = simulation(room(23), 3);
+	simulation.perform(Action::Move(Coordinate::new(12, 3)));
+	assert!(simulation.player.location == Coordinate::new(2, 3));
+	assert!(!simulation.player.memory.remembers(12, 3));
+}
+
+#[test]
+fn torches_light_distant_tiles() {
+	let mut area = room(23);
+	area.spawn("torch", Coordinate::new(12, 6)).unwrap();
+	let mut simulation = simulation(area, 3);
+	assert!(simulation.player.memory.remembers(12, 3));
+	assert!(!simulation.player.memory.remembers(6, 3));
+	simulation.perform(Action::Move(Coordinate::new(12, 3)));
+	assert!(simulation.player.location == Coordinate::new(12, 3));
+}
+
+#[test]
+fn dim_tiles_are_drawn_darker() {
+	assert!(matches!(Color::Gray.dimmed(1.0), Color::Gray));
+	assert!(matches!(Color::Black.dimmed(0.5), Color::Black));
+	assert!(matches!(
+		Color::White.dimmed(0.5),
+		Color::Rgb(127, 127, 127)
+	));
+}
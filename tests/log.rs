@@ -0,0 +1,89 @@
+mod common;
+
+use common::{room, simulation};
+use roguelike::log::message_log::{MessageCategory, MessageLog};
+
+#[test]
+fn repeats_are_collapsed_into_one_message() {
+	let mut log = MessageLog::new(10);
+	for turn in 1..=3 {
+		log.add(turn, MessageCategory::Movement, "You walk east.");
+	}
+	log.add(4, MessageCategory::Warning, "You walk east.");
+	assert_eq!(log.messages.len(), 2);
+	assert_eq!(log.messages[0].display(), "[  3] You walk east. x3");
+	assert_eq!(log.messages[1].display(), "[  4] You walk east.");
+}
+
+#[test]
+fn only_the_newest_messages_are_kept() {
+	let mut log = MessageLog::new(3);
+	for turn in 1..=5 {
+		log.add(turn, MessageCategory::Info, &format!("Message {}", turn));
+	}
+	let texts: Vec<&str> = log.messages.iter().map(|m| m.text.as_str()).collect();
+	assert_eq!(texts, ["Message 3", "Message 4", "Message 5"]);
+}
+
+#[test]
+fn scrolling_stays_within_the_log() {
+	let mut log = MessageLog::new(10);
+	log.scroll_by(-1);
+	assert_eq!(log.scroll, 0);
+	assert!(log.visible(2).is_empty());
+	for turn in 1..=4 {
+		log.add(turn, MessageCategory::Info, &format!("Message {}", turn));
+	}
+	log.scroll_by(2);
+	assert_eq!(log.visible(2)[1].text, "Message 2");
+	log.scroll_by(10);
+	assert_eq!(log.scroll, 3);
+	assert_eq!(log.visible(2).len(), 1);
+	assert_eq!(log.visible(2)[0].text, "Message 1");
+	log.scroll_by(-10);
+	assert_eq!(log.scroll, 0);
+	log.scroll_by(3);
+	log.add(5, MessageCategory::Info, "Message 5");
+	assert_eq!(log.scroll, 0);
+}
+
+#[test]
+fn the_log_survives_save_and_load() {
+	let mut log = MessageLog::new(10);
+	log.add(1, MessageCategory::Danger, "The lava burns you.");
+	log.add(2, MessageCategory::Danger, "The lava burns you.");
+	log.add(3, MessageCategory::Discovery, "You discover new ground.");
+	let loaded = MessageLog::from_json(&log.to_json(), 10).unwrap();
+	assert_eq!(loaded.messages.len(), 2);
+	for (message, original) in loaded.messages.iter().zip(&log.messages) {
+		assert_eq!(message.display(), original.display());
+		assert!(message.category == original.category);
+	}
+}
+
+#[test]
+fn logs_missing_anything_are_not_loaded() {
+	let mut log = MessageLog::new(10);
+	log.add(1, MessageCategory::Info, "You enter the dungeon.");
+	assert!(MessageLog::from_json(&json::JsonValue::Null, 10).is_none());
+	for key in ["turn", "text", "category", "count"] {
+		let mut value = log.to_json();
+		value[0].remove(key);
+		assert!(MessageLog::from_json(&value, 10).is_none(), "{}", key);
+	}
+}
+
+#[test]
+fn states_missing_anything_are_not_loaded() {
+	let mut simulation = simulation(room(9), 8);
+	let state = simulation.player.save_state();
+	for key in [
+		"area", "memory", "location", "turn", "health", "messages", "camera",
+	] {
+		let mut partial = state.clone();
+		partial.remove(key);
+		let error = simulation.player.load_state(&partial).unwrap_err();
+		assert!(error.contains(key), "{}", error);
+	}
+	assert!(simulation.player.load_state(&state).is_ok());
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::goblin_and_gold;
+use roguelike::engine::engine::{describe, Visibility};
+use roguelike::render::canvas::Color;
+use roguelike::world::world::area::Terrain;
+use roguelike::{Action, Coordinate};
+
+#[test]
+fn visible_tiles_describe_everything_on_them() {
+	let mut simulation = goblin_and_gold();
+	let goblin = describe(&mut simulation.player, Coordinate::new(8, 3));
+	assert!(goblin.visibility == Visibility::Visible);
+	assert!(goblin.terrain == Some(Terrain::Floor));
+	assert_eq!(goblin.monsters, vec!["goblin (6/6)"]);
+	let gold = describe(&mut simulation.player, Coordinate::new(8, 4));
+	assert_eq!(gold.items, vec!["pile of gold"]);
+	let wall = describe(&mut simulation.player, Coordinate::new(0, 3));
+	assert_eq!(wall.objects, vec!["wall"]);
+	let here = describe(&mut simulation.player, Coordinate::new(2, 3));
+	assert_eq!(here.monsters, vec!["you"]);
+}
+
+#[test]
+fn remembered_tiles_leave_out_what_may_have_changed() {
+	let mut simulation = goblin_and_gold();
+	simulation.player.torch_radius = 1;
+	simulation.perform(Action::None);
+	let goblin = describe(&mut simulation.player, Coordinate::new(8, 3));
+	assert!(goblin.visibility == Visibility::Remembered { last_seen: 0 });
+	assert!(goblin.monsters.is_empty());
+	let unknown = describe(&mut simulation.player, Coordinate::new(30, 30));
+	assert!(unknown.visibility == Visibility::Unknown);
+	assert!(unknown.terrain.is_none());
+	assert_eq!(unknown.to_json()["visibility"].as_str(), Some("unknown"));
+}
+
+#[test]
+fn look_mode_moves_a_cursor_and_describes_its_tile() {
+	let mut simulation = goblin_and_gold();
+	simulation.perform(Action::MoveCursor(1, 0));
+	assert!(simulation.player.inspecting.is_none());
+	simulation.perform(Action::ToggleLook);
+	for _ in 0..6 {
+		simulation.perform(Action::MoveCursor(1, 0));
+	}
+	assert!(simulation.player.inspecting == Some(Coordinate::new(8, 3)));
+	let screen = simulation.screen();
+	assert!(screen.contains("Look"));
+	assert!(screen.contains("goblin (6/6)"));
+	simulation.perform(Action::ToggleLook);
+	assert!(simulation.player.inspecting.is_none());
+	assert!(!simulation.screen().contains("goblin (6/6)"));
+}
+
+#[test]
+fn inspecting_highlights_the_tile() {
+	let mut simulation = goblin_and_gold();
+	simulation.perform(Action::Inspect(Some(Coordinate::new(8, 4))));
+	let screen = simulation
+		.player
+		.viewport
+		.to_screen(Coordinate::new(8, 4))
+		.unwrap();
+	let unit = simulation.player.canvas.get(screen.x, screen.y);
+	assert_eq!(unit.character, '$');
+	assert!(matches!(unit.bg_color, Color::Yellow));
+	assert!(simulation.screen().contains("pile of gold"));
+}
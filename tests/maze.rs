@@ -0,0 +1,140 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use roguelike::dungeon::dungeon::Dungeon;
+use roguelike::maze::maze::{generate, MazeAlgorithm, MazeConfig};
+use roguelike::Coordinate;
+use std::collections::{HashMap, VecDeque};
+
+fn maze(seed: u64, config: MazeConfig) -> Dungeon {
+	generate(config, &mut StdRng::seed_from_u64(seed))
+}
+
+/// The length of the shortest walk from `start` to every reachable floor tile
+fn distances(dungeon: &Dungeon, start: Coordinate) -> HashMap<(i32, i32), usize> {
+	let mut distances = HashMap::from([(start.as_tuple(), 0)]);
+	let mut queue = VecDeque::from([start.as_tuple()]);
+	while let Some((x, y)) = queue.pop_front() {
+		for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+			let next = (x + dx, y + dy);
+			if dungeon.area.is_walkable_at(next.0, next.1) && !distances.contains_key(&next) {
+				distances.insert(next, distances[&(x, y)] + 1);
+				queue.push_back(next);
+			}
+		}
+	}
+	distances
+}
+
+fn floor_tiles(dungeon: &Dungeon) -> usize {
+	let [min, max] = dungeon.area.bounds().unwrap();
+	(min.x..=max.x)
+		.flat_map(|x| (min.y..=max.y).map(move |y| (x, y)))
+		.filter(|(x, y)| dungeon.area.is_walkable_at(*x, *y))
+		.count()
+}
+
+/// The maze's dead ends, for corridors one tile wide
+fn dead_ends(dungeon: &Dungeon) -> usize {
+	let [min, max] = dungeon.area.bounds().unwrap();
+	let mut count = 0;
+	for x in (min.x + 1..max.x).step_by(2) {
+		for y in (min.y + 1..max.y).step_by(2) {
+			let open = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+				.iter()
+				.filter(|(dx, dy)| dungeon.area.is_walkable_at(x + dx, y + dy))
+				.count();
+			if open == 1 {
+				count += 1;
+			}
+		}
+	}
+	count
+}
+
+#[test]
+fn perfect_mazes_reach_every_cell_without_loops() {
+	for algorithm in [MazeAlgorithm::RecursiveBacktracker, MazeAlgorithm::Prim] {
+		let config = MazeConfig {
+			columns: 12,
+			rows: 8,
+			algorithm,
+			..Default::default()
+		};
+		let dungeon = maze(3, config);
+		let [min, max] = dungeon.area.bounds().unwrap();
+		assert!(min == Coordinate::new(0, 0) && max == Coordinate::new(24, 16));
+		let reached = distances(&dungeon, dungeon.spawn_point);
+		let floor = floor_tiles(&dungeon);
+		assert_eq!(reached.len(), floor);
+		// a tree of 96 cells has 95 passages, each one tile long
+		assert_eq!(floor, 96 + 95);
+	}
+}
+
+#[test]
+fn the_stairs_are_as_far_as_possible_from_the_spawn_point() {
+	// only perfect mazes are sure to have no two cells further apart
+	for braid in [0.0, 0.3] {
+		let config = MazeConfig {
+			columns: 12,
+			rows: 8,
+			braid,
+			..Default::default()
+		};
+		let dungeon = maze(5, config);
+		let from_spawn = distances(&dungeon, dungeon.spawn_point);
+		let (&stairs, &distance) = from_spawn
+			.iter()
+			.find(|((x, y), _)| dungeon.area.get_tile_at(*x, *y).contains_stairs())
+			.unwrap();
+		assert_eq!(distance, *from_spawn.values().max().unwrap());
+		if braid > 0.0 {
+			continue;
+		}
+		let from_stairs = distances(&dungeon, Coordinate::new(stairs.0, stairs.1));
+		for (x, y) in from_spawn.keys() {
+			let from_here = distances(&dungeon, Coordinate::new(*x, *y));
+			assert!(*from_here.values().max().unwrap() <= distance);
+			assert!(from_stairs[&(*x, *y)] <= distance);
+		}
+	}
+}
+
+#[test]
+fn corridors_can_be_wider() {
+	let config = MazeConfig {
+		columns: 6,
+		rows: 4,
+		corridor_width: 3,
+		..Default::default()
+	};
+	let dungeon = maze(2, config);
+	let [_, max] = dungeon.area.bounds().unwrap();
+	assert!(max == Coordinate::new(24, 16));
+	assert_eq!(floor_tiles(&dungeon), 24 * 9 + 23 * 3);
+	assert_eq!(
+		distances(&dungeon, dungeon.spawn_point).len(),
+		floor_tiles(&dungeon)
+	);
+}
+
+#[test]
+fn braiding_removes_dead_ends() {
+	let config = |braid| MazeConfig {
+		columns: 20,
+		rows: 12,
+		algorithm: MazeAlgorithm::Prim,
+		braid,
+		..Default::default()
+	};
+	let perfect = dead_ends(&maze(9, config(0.0)));
+	let half = dead_ends(&maze(9, config(0.5)));
+	let braided = maze(9, config(1.0));
+	assert!(perfect > 0);
+	assert!(half < perfect && half > 0);
+	assert_eq!(dead_ends(&braided), 0);
+	assert_eq!(
+		distances(&braided, braided.spawn_point).len(),
+		floor_tiles(&braided)
+	);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::goblin_and_gold;
+use roguelike::memory::memory_map::MemoryMap;
+use roguelike::{Action, Coordinate};
+
+#[test]
+fn memory_leaves_out_monsters_but_keeps_items() {
+	let simulation = goblin_and_gold();
+	let memory = &simulation.player.memory;
+	assert_eq!(simulation.player.area.appearance_at(8, 3).0, 'g');
+	assert_eq!(memory.appearance_at(8, 3).unwrap().0, ' ');
+	assert_eq!(memory.appearance_at(8, 4).unwrap().0, '$');
+	assert!(memory.get(0, 3).unwrap().wall);
+	assert!(memory.appearance_at(20, 20).is_none());
+}
+
+#[test]
+fn memory_records_when_tiles_were_last_seen() {
+	let mut simulation = goblin_and_gold();
+	simulation.player.torch_radius = 2;
+	simulation.perform(Action::Move(Coordinate::new(3, 3)));
+	let turn = simulation.player.turn;
+	assert!(turn > 0);
+	assert_eq!(simulation.player.memory.get(4, 3).unwrap().last_seen, turn);
+	assert_eq!(simulation.player.memory.get(8, 3).unwrap().last_seen, 0);
+}
+
+#[test]
+fn memory_survives_save_and_load() {
+	let simulation = goblin_and_gold();
+	let memory = &simulation.player.memory;
+	let loaded = MemoryMap::from_json(&memory.to_json()).unwrap();
+	assert_eq!(loaded.tile_count(), memory.tile_count());
+	assert_eq!(loaded.appearance_at(8, 4).unwrap().0, '$');
+	assert!(loaded.get(8, 4).unwrap().bg_color.is_none());
+	assert!(loaded.get(0, 3).unwrap().bg_color.is_some());
+}
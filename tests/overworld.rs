@@ -0,0 +1,205 @@
+use roguelike::engine::engine::Player;
+use roguelike::overworld::overworld::Overworld;
+use roguelike::population::population::PopulationConfig;
+use roguelike::world::world::area::Terrain;
+use roguelike::{Action, Coordinate, Simulation};
+use std::collections::HashSet;
+
+fn outdoors(seed: u64) -> Simulation {
+	let mut simulation = Simulation {
+		player: Player::outdoors(Overworld::new(seed)),
+	};
+	simulation.perform(Action::None);
+	simulation
+}
+
+/// Stand next to `target` and then walk onto it
+fn step_onto(simulation: &mut Simulation, target: Coordinate) {
+	let area = &simulation.player.area;
+	let next_to = (-1..=1)
+		.flat_map(|dx| (-1..=1).map(move |dy| Coordinate::new(target.x + dx, target.y + dy)))
+		.find(|tile| {
+			*tile != target
+				&& area.is_walkable_at(tile.x, tile.y)
+				&& !area.get_tile_at(tile.x, tile.y).contains_stairs()
+				&& !area.get_tile_at(tile.x, tile.y).contains_up_stairs()
+		})
+		.unwrap();
+	simulation.player.location = next_to;
+	simulation.perform(Action::None);
+	simulation.perform(Action::Move(target));
+}
+
+#[test]
+fn the_outdoors_has_varied_terrain_and_reachable_entrances() {
+	let simulation = outdoors(4);
+	let overworld = simulation.player.overworld.as_ref().unwrap();
+	let area = &simulation.player.area;
+	let mut terrains = HashSet::new();
+	for x in 0..overworld.width {
+		for y in 0..overworld.height {
+			let terrain = area.get_tile_at(x, y).terrain;
+			terrains.insert(terrain.name());
+			if x == 0 || y == 0 || x == overworld.width - 1 || y == overworld.height - 1 {
+				assert!(terrain == Terrain::Mountain);
+			}
+		}
+	}
+	for terrain in ["meadow", "forest", "water", "mountain"] {
+		assert!(terrains.contains(terrain));
+	}
+	assert_eq!(overworld.entrances.len(), 4);
+	for entrance in &overworld.entrances {
+		assert!(area.get_tile_at(entrance.x, entrance.y).contains_stairs());
+		assert!(*entrance != simulation.player.location);
+	}
+}
+
+#[test]
+fn entrances_lead_into_dungeons_and_back_out() {
+	let mut simulation = outdoors(4);
+	let entrance = simulation.player.overworld.as_ref().unwrap().entrances[1];
+	step_onto(&mut simulation, entrance);
+	assert_eq!(
+		simulation.player.overworld.as_ref().unwrap().current,
+		Some(1)
+	);
+	assert!(simulation
+		.screen()
+		.contains("You climb down into the dungeon."));
+	let arrival = simulation.player.location;
+	assert!(simulation
+		.player
+		.area
+		.get_tile_at(arrival.x, arrival.y)
+		.contains_up_stairs());
+	let gold = simulation
+		.player
+		.area
+		.spawn("gold", Coordinate::new(arrival.x, arrival.y))
+		.unwrap();
+	let remembered = simulation.player.memory.tile_count();
+	assert!(remembered > 0);
+
+	step_onto(&mut simulation, arrival);
+	assert!(simulation
+		.player
+		.overworld
+		.as_ref()
+		.unwrap()
+		.current
+		.is_none());
+	assert!(simulation.player.location == entrance);
+	assert!(simulation
+		.screen()
+		.contains("You climb back up to the surface."));
+	assert!(simulation
+		.player
+		.area
+		.get_tile_at(entrance.x, entrance.y)
+		.contains_stairs());
+
+	// the dungeon is as it was left
+	step_onto(&mut simulation, entrance);
+	assert!(simulation.player.location == arrival);
+	assert_eq!(
+		simulation.player.area.entities_at(arrival.x, arrival.y),
+		&[gold]
+	);
+	assert!(simulation.player.memory.tile_count() >= remembered);
+}
+
+#[test]
+fn every_entrance_leads_somewhere_to_stand() {
+	let mut simulation = outdoors(9);
+	let entrances = simulation
+		.player
+		.overworld
+		.as_ref()
+		.unwrap()
+		.entrances
+		.clone();
+	for (index, entrance) in entrances.into_iter().enumerate() {
+		step_onto(&mut simulation, entrance);
+		assert_eq!(
+			simulation.player.overworld.as_ref().unwrap().current,
+			Some(index)
+		);
+		let location = simulation.player.location;
+		assert!(simulation
+			.player
+			.area
+			.is_walkable_at(location.x, location.y));
+		step_onto(&mut simulation, location);
+		assert!(simulation.player.location == entrance);
+	}
+}
+
+#[test]
+fn later_entrances_lead_to_more_dangerous_dungeons() {
+	let mut simulation = outdoors(9);
+	let entrances = simulation
+		.player
+		.overworld
+		.as_ref()
+		.unwrap()
+		.entrances
+		.clone();
+	let config = PopulationConfig::default();
+	for (index, entrance) in entrances.into_iter().enumerate() {
+		step_onto(&mut simulation, entrance);
+		let depth = Overworld::depth(index);
+		let entities = &simulation.player.area.entities;
+		let spent: i32 = entities
+			.definitions
+			.values()
+			.filter_map(|id| config.monsters.iter().find(|entry| entry.definition == *id))
+			.map(|entry| {
+				assert!(entry.min_depth <= depth);
+				entry.cost
+			})
+			.sum();
+		assert_eq!(
+			spent,
+			config.monster_budget + config.monster_budget_per_depth * (depth - 1)
+		);
+		let location = simulation.player.location;
+		step_onto(&mut simulation, location);
+	}
+}
+
+#[test]
+fn games_in_a_dungeon_survive_save_and_load() {
+	let mut simulation = outdoors(4);
+	let entrance = simulation.player.overworld.as_ref().unwrap().entrances[0];
+	step_onto(&mut simulation, entrance);
+	let arrival = simulation.player.location;
+	let state = simulation.player.save_state();
+
+	let mut loaded = Simulation {
+		player: Player::outdoors(Overworld::new(1)),
+	};
+	loaded.player.load_state(&state).unwrap();
+	assert_eq!(loaded.player.overworld.as_ref().unwrap().current, Some(0));
+	step_onto(&mut loaded, arrival);
+	assert!(loaded.player.location == entrance);
+	assert!(loaded
+		.player
+		.area
+		.get_tile_at(entrance.x, entrance.y)
+		.contains_stairs());
+}
+
+#[test]
+fn new_games_start_in_a_dungeon_outdoors_or_in_an_open_world() {
+	assert!(roguelike::PLAYER.read().unwrap().overworld.is_none());
+	assert!(roguelike::new_game("outdoors").contains("canvases"));
+	assert!(roguelike::PLAYER.read().unwrap().overworld.is_some());
+	assert!(roguelike::new_game("underwater").contains("error"));
+	assert!(roguelike::PLAYER.read().unwrap().overworld.is_some());
+	roguelike::new_game("world");
+	assert!(roguelike::PLAYER.read().unwrap().world.is_some());
+	roguelike::new_game("dungeon");
+	let player = roguelike::PLAYER.read().unwrap();
+	assert!(player.overworld.is_none() && player.world.is_none());
+}
@@ -0,0 +1,191 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use roguelike::content::definitions::definitions;
+use roguelike::dungeon::dungeon::{Dungeon, DungeonConfig};
+use roguelike::engine::engine::Player;
+use roguelike::population::population::{populate, Placement, PopulationConfig, SpawnEntry};
+use roguelike::Simulation;
+
+fn dungeon(seed: u64) -> Dungeon {
+	let config = DungeonConfig {
+		dungeon_width: 80,
+		dungeon_height: 40,
+		..Default::default()
+	};
+	Dungeon::new(config, &mut StdRng::seed_from_u64(seed)).unwrap()
+}
+
+fn populated(seed: u64, depth: i32) -> (Dungeon, Vec<Placement>) {
+	let mut dungeon = dungeon(seed);
+	let placements = populate(
+		&mut dungeon,
+		&PopulationConfig::default(),
+		depth,
+		&mut StdRng::seed_from_u64(seed),
+	);
+	(dungeon, placements)
+}
+
+/// How much of the budget went on monsters and how much on items
+fn spent(placements: &[Placement]) -> (i32, i32) {
+	let config = PopulationConfig::default();
+	let cost_in = |table: &[SpawnEntry]| {
+		placements
+			.iter()
+			.filter(|placement| {
+				table
+					.iter()
+					.any(|entry| entry.definition == placement.definition)
+			})
+			.map(|placement| placement.cost)
+			.sum()
+	};
+	(cost_in(&config.monsters), cost_in(&config.items))
+}
+
+#[test]
+fn budgets_are_kept_to_and_grow_with_depth() {
+	let config = PopulationConfig::default();
+	for seed in 1..=5 {
+		let mut last = (0, 0);
+		for depth in 1..=6 {
+			let (_, placements) = populated(seed, depth);
+			let (monsters, items) = spent(&placements);
+			assert!(
+				monsters <= config.monster_budget + config.monster_budget_per_depth * (depth - 1)
+			);
+			assert!(items <= config.item_budget + config.item_budget_per_depth * (depth - 1));
+			// every table has something costing 1, so the whole budget gets spent
+			assert!(monsters > last.0 && items > last.1);
+			last = (monsters, items);
+		}
+	}
+}
+
+#[test]
+fn nothing_is_placed_near_the_spawn_point() {
+	let clearance = PopulationConfig::default().spawn_clearance;
+	for seed in 1..=10 {
+		let (dungeon, placements) = populated(seed, 6);
+		let spawn = dungeon.spawn_point;
+		let [min, max] = *dungeon
+			.area
+			.rooms
+			.iter()
+			.find(|[min, max]| {
+				spawn.x >= min.x && spawn.x <= max.x && spawn.y >= min.y && spawn.y <= max.y
+			})
+			.unwrap();
+		for placement in &placements {
+			let position = placement.position;
+			assert!(
+				(position.x - spawn.x)
+					.abs()
+					.max((position.y - spawn.y).abs())
+					> clearance
+			);
+			assert!(
+				position.x < min.x
+					|| position.x > max.x
+					|| position.y < min.y
+					|| position.y > max.y,
+				"seed {} put {} in the spawn room",
+				seed,
+				placement.definition
+			);
+			assert!(!dungeon
+				.area
+				.get_tile_at(position.x, position.y)
+				.contains_wall());
+		}
+	}
+}
+
+#[test]
+fn tougher_spawns_wait_for_their_depth() {
+	let config = PopulationConfig::default();
+	let mut seen_deep = false;
+	for seed in 1..=10 {
+		for depth in [1, 6] {
+			let (dungeon, placements) = populated(seed, depth);
+			for placement in &placements {
+				let entry = config
+					.monsters
+					.iter()
+					.chain(&config.items)
+					.find(|entry| entry.definition == placement.definition)
+					.unwrap();
+				assert!(entry.min_depth <= depth);
+				seen_deep |= entry.min_depth > 1;
+				assert!(definitions().get(&placement.definition).is_some());
+				let (x, y) = placement.position.as_tuple::<i32>();
+				assert!(dungeon.area.entities.at(x, y).contains(&placement.entity));
+			}
+		}
+	}
+	assert!(seen_deep);
+}
+
+#[test]
+fn the_same_seed_populates_the_same_way() {
+	let positions = |placements: Vec<Placement>| -> Vec<(String, i32, i32)> {
+		placements
+			.into_iter()
+			.map(|placement| {
+				(
+					placement.definition,
+					placement.position.x,
+					placement.position.y,
+				)
+			})
+			.collect()
+	};
+	assert_eq!(positions(populated(4, 3).1), positions(populated(4, 3).1));
+	assert_ne!(positions(populated(4, 3).1), positions(populated(5, 3).1));
+}
+
+#[test]
+fn spawn_tables_come_from_the_definitions() {
+	let config = PopulationConfig::default();
+	let rat = config
+		.monsters
+		.iter()
+		.find(|entry| entry.definition == "rat")
+		.unwrap();
+	assert_eq!((rat.weight, rat.cost, rat.min_depth), (8.0, 1, 1));
+	assert!(config.items.iter().any(|entry| entry.definition == "sword"));
+	assert!(!config
+		.monsters
+		.iter()
+		.any(|entry| entry.definition == "torch"));
+}
+
+#[test]
+fn entries_without_a_definition_cost_nothing() {
+	let config = PopulationConfig {
+		monsters: vec![
+			SpawnEntry::new("dragon", 100.0, 1, 1),
+			SpawnEntry::new("rat", 1.0, 1, 1),
+		],
+		items: vec![],
+		..Default::default()
+	};
+	let mut dungeon = dungeon(2);
+	let placements = populate(&mut dungeon, &config, 1, &mut StdRng::seed_from_u64(2));
+	assert_eq!(placements.len() as i32, config.monster_budget);
+	assert!(placements
+		.iter()
+		.all(|placement| placement.definition == "rat"));
+}
+
+#[test]
+fn new_games_start_with_monsters_and_loot() {
+	let populated = |player: &Player| {
+		let entities = &player.area.entities;
+		!entities.health.is_empty() && !entities.items.is_empty()
+	};
+	assert!(populated(&Simulation::new(3).player));
+	assert!(populated(&roguelike::PLAYER.read().unwrap()));
+	roguelike::new_game("dungeon");
+	assert!(populated(&roguelike::PLAYER.read().unwrap()));
+}
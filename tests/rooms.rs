@@ -0,0 +1,187 @@
+mod common;
+
+use common::{assert_connected, reachable};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use roguelike::dungeon::dungeon::{Dungeon, DungeonConfig, RoomShape};
+
+const SHAPES: [RoomShape; 6] = [
+	RoomShape::Rectangle,
+	RoomShape::Circle,
+	RoomShape::Cross,
+	RoomShape::LShape,
+	RoomShape::PillaredHall,
+	RoomShape::Cave,
+];
+
+fn dungeon(seed: u64, shape: RoomShape) -> Dungeon {
+	generate(
+		seed,
+		DungeonConfig {
+			room_shapes: vec![(shape, 1.0)],
+			..config()
+		},
+	)
+}
+
+fn config() -> DungeonConfig {
+	DungeonConfig {
+		dungeon_width: 80,
+		dungeon_height: 40,
+		terrain_feature_chance: 0.0,
+		..Default::default()
+	}
+}
+
+fn generate(seed: u64, config: DungeonConfig) -> Dungeon {
+	Dungeon::new(config, &mut StdRng::seed_from_u64(seed)).unwrap()
+}
+
+/// How many rooms have a wall somewhere inside their outer walls
+fn rooms_with_inner_walls(dungeon: &Dungeon) -> usize {
+	dungeon
+		.area
+		.rooms
+		.iter()
+		.filter(|[min, max]| {
+			(min.x + 1..max.x)
+				.any(|x| (min.y + 1..max.y).any(|y| dungeon.area.get_tile_at(x, y).contains_wall()))
+		})
+		.count()
+}
+
+#[test]
+fn hallways_reach_the_floor_of_every_room_shape() {
+	for shape in SHAPES {
+		for seed in 3..=6 {
+			assert_connected(
+				&dungeon(seed, shape),
+				&format!("{:?} rooms with seed {}", shape, seed),
+			);
+		}
+	}
+}
+
+#[test]
+fn shaped_rooms_fill_only_part_of_their_bounds() {
+	for shape in SHAPES {
+		let walled = rooms_with_inner_walls(&dungeon(3, shape));
+		if shape == RoomShape::Rectangle {
+			assert_eq!(walled, 0);
+		} else {
+			assert!(walled > 0, "no {:?} room has walls inside it", shape);
+		}
+	}
+}
+
+#[test]
+fn stairs_and_spawn_point_are_on_floor() {
+	for shape in SHAPES {
+		let dungeon = dungeon(5, shape);
+		let spawn = dungeon.spawn_point;
+		assert!(dungeon.area.is_walkable_at(spawn.x, spawn.y));
+		let stairs = reachable(&dungeon.area, dungeon.spawn_point)
+			.into_iter()
+			.filter(|(x, y)| dungeon.area.get_tile_at(*x, *y).contains_stairs())
+			.count();
+		assert_eq!(stairs, 1, "{:?} rooms hide the stairs", shape);
+	}
+}
+
+#[test]
+fn rooms_keep_to_the_size_and_spacing_limits() {
+	let padding = 2;
+	for seed in 1..=4 {
+		let dungeon = generate(
+			seed,
+			DungeonConfig {
+				max_aspect_ratio: 1.5,
+				min_room_area: 60,
+				max_room_area: Some(150),
+				room_padding: padding,
+				..config()
+			},
+		);
+		let rooms = &dungeon.area.rooms;
+		assert!(rooms.len() > 1);
+		for [min, max] in rooms {
+			let (width, height) = (max.x - min.x + 1, max.y - min.y + 1);
+			assert!(width * height >= 60 && width * height <= 150);
+			assert!(width.max(height) as f64 <= width.min(height) as f64 * 1.5);
+		}
+		// rooms in neighbouring leaves are apart by both paddings and the gap between the leaves
+		for (i, [min_1, max_1]) in rooms.iter().enumerate() {
+			for [min_2, max_2] in &rooms[i + 1..] {
+				let gap = (min_2.x - max_1.x)
+					.max(min_1.x - max_2.x)
+					.max(min_2.y - max_1.y)
+					.max(min_1.y - max_2.y);
+				assert!(gap >= 2 * padding + 2);
+			}
+		}
+		assert_connected(&dungeon, &format!("padded rooms with seed {}", seed));
+	}
+}
+
+#[test]
+fn empty_leaves_leave_fewer_rooms_that_still_connect() {
+	let (mut full, mut sparse) = (0, 0);
+	for seed in 1..=5 {
+		full += generate(seed, config()).area.rooms.len();
+		for chance in [0.5, 1.0] {
+			let dungeon = generate(
+				seed,
+				DungeonConfig {
+					empty_leaf_chance: chance,
+					..config()
+				},
+			);
+			if chance == 0.5 {
+				sparse += dungeon.area.rooms.len();
+			}
+			assert!(!dungeon.area.rooms.is_empty());
+			assert_connected(&dungeon, &format!("empty leaves with seed {}", seed));
+		}
+	}
+	assert!(sparse < full);
+}
+
+#[test]
+fn leaves_too_small_for_any_room_still_give_one_within_the_limits() {
+	let dungeon = generate(
+		1,
+		DungeonConfig {
+			min_room_area: 1500,
+			max_room_area: Some(1600),
+			..config()
+		},
+	);
+	assert_eq!(dungeon.area.rooms.len(), 1);
+	for [min, max] in &dungeon.area.rooms {
+		let (width, height) = (max.x - min.x + 1, max.y - min.y + 1);
+		assert!(width * height >= 1500 && width * height <= 1600);
+		assert!(width.max(height) <= width.min(height) * 3);
+	}
+	let spawn = dungeon.spawn_point;
+	assert!(dungeon.area.is_walkable_at(spawn.x, spawn.y));
+}
+
+#[test]
+fn limits_no_room_can_meet_are_rejected() {
+	for config in [
+		DungeonConfig {
+			min_room_area: 10_000,
+			..config()
+		},
+		DungeonConfig {
+			max_room_area: Some(20),
+			..config()
+		},
+		DungeonConfig {
+			room_padding: 38,
+			..config()
+		},
+	] {
+		assert!(Dungeon::new(config, &mut StdRng::seed_from_u64(1)).is_err());
+	}
+}
@@ -0,0 +1,140 @@
+//! Golden snapshots of generated dungeons and rendered screens.
+//!
+//! Run with `BLESS=1 cargo test --test snapshots` to write new snapshots after an intended change.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use roguelike::dungeon::dungeon::{Dungeon, DungeonConfig};
+use roguelike::engine::engine::{draw_area, draw_map};
+use roguelike::render::canvas::{Canvas, Color};
+use roguelike::{Coordinate, Simulation};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The canvas as text, with blank cells shown by their background color.
+/// Dimmed grays are shown by their brightness from 0 to 9, and other dimmed colors by their strongest channel.
+fn canvas_text(canvas: &Canvas) -> String {
+	let mut text = String::new();
+	for y in (0..canvas.height).rev() {
+		for x in 0..canvas.width {
+			let unit = canvas.get(x, y);
+			text.push(if unit.character != ' ' {
+				unit.character
+			} else {
+				match unit.bg_color {
+					Color::Black => ' ',
+					Color::White => '#',
+					Color::Gray => '.',
+					Color::DarkGray => ',',
+					Color::LightGray => ':',
+					Color::Red => '=',
+					Color::Blue => '~',
+					Color::Rgb(r, g, b) if r == g && g == b => {
+						char::from_digit(r as u32 * 10 / 256, 10).unwrap()
+					}
+					Color::Rgb(r, g, b) if b > r && b > g => '~',
+					Color::Rgb(r, g, _) if g > r => '"',
+					Color::Rgb(..) => '=',
+					_ => '?',
+				}
+			});
+		}
+		text.push('\n');
+	}
+	text
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+	let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", name]
+		.iter()
+		.collect::<PathBuf>()
+		.with_extension("txt");
+	if env::var_os("BLESS").is_some() {
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(&path, actual).unwrap();
+		return;
+	}
+	let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+		panic!(
+			"Missing snapshot {}, run with BLESS=1 to create it",
+			path.display()
+		)
+	});
+	if expected == actual {
+		return;
+	}
+	let mut report = String::new();
+	for (i, (expected_line, actual_line)) in expected.lines().zip(actual.lines()).enumerate() {
+		if expected_line != actual_line {
+			report.push_str(&format!(
+				"line {}\n  expected: {}\n  actual:   {}\n",
+				i + 1,
+				expected_line,
+				actual_line
+			));
+		}
+	}
+	panic!(
+		"Snapshot {} differs, run with BLESS=1 to accept the change\n{}\nactual:\n{}",
+		path.display(),
+		report,
+		actual
+	);
+}
+
+fn dungeon_text(seed: u64, config: DungeonConfig) -> String {
+	let (width, height) = (config.dungeon_width, config.dungeon_height);
+	let dungeon = Dungeon::new(config, &mut StdRng::seed_from_u64(seed)).unwrap();
+	let mut canvas = Canvas::new(width, height);
+	draw_map(&dungeon.area, &mut canvas);
+	canvas.set(
+		dungeon.spawn_point.x,
+		dungeon.spawn_point.y,
+		'O',
+		Color::Gray,
+		roguelike::Action::None,
+	);
+	canvas_text(&canvas)
+}
+
+#[test]
+fn default_dungeons() {
+	for seed in 1..=3 {
+		assert_snapshot(
+			&format!("dungeon_default_{}", seed),
+			&dungeon_text(seed, DungeonConfig::default()),
+		);
+	}
+}
+
+#[test]
+fn small_dungeon() {
+	let config = DungeonConfig {
+		dungeon_width: 60,
+		dungeon_height: 30,
+		max_split_iterations: 3,
+		..DungeonConfig::default()
+	};
+	assert_snapshot("dungeon_small", &dungeon_text(4, config));
+}
+
+#[test]
+fn draw_area_viewport() {
+	let mut simulation = Simulation::new(1);
+	simulation.player.canvas = Canvas::new(41, 21);
+	draw_area(
+		&mut simulation.player,
+		Coordinate::new(0, 0),
+		Coordinate::new(41, 21),
+	);
+	assert_snapshot("viewport", &canvas_text(&simulation.player.canvas));
+}
+
+#[test]
+fn starting_screen() {
+	assert_snapshot(
+		"screen_start",
+		&canvas_text(&Simulation::new(1).player.canvas),
+	);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{room, simulation};
+use roguelike::engine::engine::{start_activity, tick, Activity, Progress};
+use roguelike::{Action, Coordinate};
+
+#[test]
+fn travel_takes_one_step_per_tick() {
+	let mut simulation = simulation(room(23), 8);
+	let path = (3..=5).map(|x| Coordinate::new(x, 3)).collect();
+	start_activity(&mut simulation.player, Activity::Travel { path });
+	assert!(matches!(tick(&mut simulation.player), Progress::Continuing));
+	assert!(simulation.player.location == Coordinate::new(3, 3));
+	assert!(matches!(tick(&mut simulation.player), Progress::Continuing));
+	assert!(matches!(
+		tick(&mut simulation.player),
+		Progress::Finished(None)
+	));
+	assert!(simulation.player.location == Coordinate::new(5, 3));
+	assert!(simulation.player.activity.is_none());
+}
+
+#[test]
+fn travel_stops_when_a_monster_comes_into_view() {
+	let mut area = room(23);
+	area.spawn("goblin", Coordinate::new(8, 5)).unwrap();
+	let mut simulation = simulation(area, 4);
+	let outcome = simulation.run(&[Action::Move(Coordinate::new(6, 3))]);
+	assert!(simulation.player.location == Coordinate::new(5, 3));
+	assert_eq!(outcome.frames, 3);
+	assert!(simulation.screen().contains("You notice a goblin."));
+}
+
+#[test]
+fn travel_ignores_what_was_already_in_view() {
+	let mut area = room(23);
+	area.spawn("goblin", Coordinate::new(8, 5)).unwrap();
+	let mut simulation = simulation(area, 8);
+	simulation.perform(Action::Move(Coordinate::new(6, 3)));
+	assert!(simulation.player.location == Coordinate::new(6, 3));
+}
+
+#[test]
+fn travel_walks_past_objects_that_are_not_items() {
+	let mut area = room(23);
+	area.spawn("torch", Coordinate::new(4, 3)).unwrap();
+	let mut simulation = simulation(area, 8);
+	simulation.perform(Action::Move(Coordinate::new(8, 3)));
+	assert!(simulation.player.location == Coordinate::new(8, 3));
+	assert!(!simulation.screen().contains("wall torch"));
+}
+
+#[test]
+fn travel_stops_on_items() {
+	let mut area = room(23);
+	area.spawn("gold", Coordinate::new(4, 3)).unwrap();
+	let mut simulation = simulation(area, 8);
+	simulation.perform(Action::Move(Coordinate::new(8, 3)));
+	assert!(simulation.player.location == Coordinate::new(4, 3));
+	assert!(simulation
+		.screen()
+		.contains("There is a pile of gold here."));
+}
+
+#[test]
+fn loading_a_game_stops_travel() {
+	let mut simulation = simulation(room(23), 8);
+	let state = simulation.player.save_state();
+	let path = (3..=5).map(|x| Coordinate::new(x, 3)).collect();
+	start_activity(&mut simulation.player, Activity::Travel { path });
+	simulation.perform(Action::ToggleLook);
+	simulation.player.load_state(&state).unwrap();
+	assert!(simulation.player.activity.is_none());
+	assert!(simulation.player.inspecting.is_none() && !simulation.player.look_mode);
+	simulation.perform(Action::Resume);
+	assert!(simulation.player.location == Coordinate::new(2, 3));
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::reachable;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use roguelike::dungeon::dungeon::Dungeon;
+use roguelike::walker::walker::{generate, WalkerConfig};
+
+fn walk(seed: u64, config: WalkerConfig) -> Dungeon {
+	generate(config, &mut StdRng::seed_from_u64(seed))
+}
+
+#[test]
+fn walkers_carve_a_connected_walled_level() {
+	let dungeon = walk(3, WalkerConfig::default());
+	let area = &dungeon.area;
+	assert!(area.is_walkable_at(dungeon.spawn_point.x, dungeon.spawn_point.y));
+	let floor = reachable(&dungeon.area, dungeon.spawn_point);
+	let mut stairs = 0;
+	for (x, x_col) in &area.map {
+		for (y, tile) in x_col {
+			if tile.contains_stairs() {
+				stairs += 1;
+			}
+			if tile.contains_wall() {
+				continue;
+			}
+			assert!(floor.contains(&(*x, *y)));
+			// floor never touches the unset void around the level
+			for dx in -1..=1 {
+				for dy in -1..=1 {
+					assert!(area.tile_exists(x + dx, y + dy));
+				}
+			}
+		}
+	}
+	assert_eq!(stairs, 1);
+}
+
+#[test]
+fn coverage_sets_how_much_is_carved() {
+	for coverage in [0.2, 0.5] {
+		let config = WalkerConfig {
+			width: 40,
+			height: 20,
+			coverage,
+			..Default::default()
+		};
+		let dungeon = walk(8, config);
+		let floor = reachable(&dungeon.area, dungeon.spawn_point).len() as f64;
+		let inside = (38 * 18) as f64;
+		assert!(floor >= inside * coverage);
+		assert!(floor < inside * coverage + 4.0);
+	}
+}
+
+#[test]
+fn levels_stay_inside_their_bounds() {
+	let config = WalkerConfig {
+		width: 30,
+		height: 12,
+		walkers: 1,
+		coverage: 0.9,
+		exploration_bias: 0.0,
+	};
+	let dungeon = walk(1, config);
+	let [min, max] = dungeon.area.bounds().unwrap();
+	assert!(min.x >= 0 && min.y >= 0);
+	assert!(max.x <= 29 && max.y <= 11);
+}
+
+#[test]
+fn same_seed_walks_the_same_level() {
+	let a = walk(11, WalkerConfig::default());
+	let b = walk(11, WalkerConfig::default());
+	assert_eq!(tiles(&a), tiles(&b));
+	assert!(a.spawn_point == b.spawn_point);
+}
+
+/// The glyph of every set tile, in order of position
+fn tiles(dungeon: &Dungeon) -> Vec<(i32, i32, char)> {
+	let mut tiles = vec![];
+	for (x, x_col) in &dungeon.area.map {
+		for (y, tile) in x_col {
+			tiles.push((*x, *y, tile.get_char()));
+		}
+	}
+	tiles.sort_unstable();
+	tiles
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::reachable;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use roguelike::dungeon::dungeon::Dungeon;
+use roguelike::wfc::wfc::{generate, WfcConfig, ROOMS_SAMPLE};
+
+fn collapse(seed: u64, sample: &str, config: WfcConfig) -> Result<Dungeon, String> {
+	generate(sample, config, &mut StdRng::seed_from_u64(seed))
+}
+
+#[test]
+fn levels_are_walled_in_and_fully_connected() {
+	let dungeon = collapse(2, ROOMS_SAMPLE, WfcConfig::default()).unwrap();
+	let area = &dungeon.area;
+	let [min, max] = area.bounds().unwrap();
+	assert!(min.x == 0 && min.y == 0 && max.x == 59 && max.y == 29);
+	let floor = reachable(&dungeon.area, dungeon.spawn_point);
+	assert!(floor.len() > 100);
+	let mut stairs = 0;
+	for x in 0..60 {
+		for y in 0..30 {
+			let tile = area.get_tile_at(x, y);
+			if x == 0 || y == 0 || x == 59 || y == 29 {
+				assert!(tile.contains_wall());
+			}
+			if tile.contains_stairs() {
+				stairs += 1;
+			}
+			assert_eq!(!tile.contains_wall(), floor.contains(&(x, y)));
+		}
+	}
+	assert_eq!(stairs, 1);
+}
+
+#[test]
+fn levels_only_contain_patterns_from_the_sample() {
+	// with no doors in the sample there are none in the level,
+	// and 2x2 blocks of floor only appear where the sample has rooms
+	let sample = "
+		#########
+		#.......#
+		#.#####.#
+		#.#####.#
+		#.......#
+		#########";
+	let config = WfcConfig {
+		width: 30,
+		height: 20,
+		pattern_size: 2,
+		..Default::default()
+	};
+	let dungeon = collapse(4, &sample.replace('\t', ""), config).unwrap();
+	let area = &dungeon.area;
+	assert!(area.entities.ids().is_empty());
+	for x in 0..29 {
+		for y in 0..19 {
+			let open = [(0, 0), (1, 0), (0, 1), (1, 1)]
+				.iter()
+				.filter(|(dx, dy)| !area.get_tile_at(x + dx, y + dy).contains_wall())
+				.count();
+			assert!(open < 4);
+		}
+	}
+}
+
+#[test]
+fn doors_are_placed_as_entities() {
+	let dungeon = collapse(6, ROOMS_SAMPLE, WfcConfig::default()).unwrap();
+	let doors: Vec<_> = dungeon
+		.area
+		.entities
+		.ids()
+		.into_iter()
+		.filter(|id| dungeon.area.entities.name(*id) == "door")
+		.collect();
+	assert!(!doors.is_empty());
+	for door in doors {
+		let position = dungeon.area.entities.positions[&door];
+		assert!(dungeon.area.is_walkable_at(position.x, position.y));
+		assert!(dungeon.area.blocks_sight_at(position.x, position.y));
+	}
+}
+
+#[test]
+fn invalid_samples_are_reported() {
+	assert!(collapse(1, "", WfcConfig::default()).is_err());
+	assert!(collapse(1, "##\n#x", WfcConfig::default()).is_err());
+	assert!(collapse(1, "###\n#.", WfcConfig::default()).is_err());
+	assert!(collapse(1, "##\n##", WfcConfig::default()).is_err());
+	let tiny = WfcConfig {
+		width: 4,
+		height: 4,
+		..Default::default()
+	};
+	assert!(collapse(1, ROOMS_SAMPLE, tiny).is_err());
+}
+
+#[test]
+fn all_walls_fail_after_retries() {
+	let config = WfcConfig {
+		retries: 2,
+		..Default::default()
+	};
+	let error = collapse(1, "####\n####\n####", config).err().unwrap();
+	assert!(error.contains("3 attempts"));
+}